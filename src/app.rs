@@ -1,8 +1,9 @@
 use std::{
     cell::RefCell,
+    fs::File,
     io::{stdout, Write},
     rc::Rc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crossterm::{
@@ -10,19 +11,28 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode},
 };
 use termint::{
-    enums::Modifier,
+    enums::{Color, Modifier},
     geometry::{Constraint, TextAlign},
     term::Term,
-    widgets::{Layout, ListState, StrSpanExtension},
+    widgets::{Layout, ListState, Span, StrSpanExtension},
 };
 
 use crate::{
-    config::Config, error::Error, scramble::Scramble, stats::stats::Stats,
+    config::Config,
+    error::Error,
+    scramble::Scramble,
+    session_dialog::SessionDialog,
+    stats::{records::Record, stats::Stats},
+    storage,
 };
 
 #[derive(Debug, Clone, Default)]
 pub enum Screen {
     Timer,
+    Averages,
+    Detail,
+    Comment,
+    LoadError,
     #[default]
     Sessions,
 }
@@ -37,37 +47,53 @@ pub struct App {
     pub scramble: Option<Scramble>,
     pub stats: Stats,
     pub time: Duration,
+    pub pb_flash: Option<(Instant, Vec<Record>)>,
+    pub input: String,
+    pub session_dialog: Option<SessionDialog>,
+    pub load_error: Option<String>,
+    /// Lock held while the app is running
+    pub instance_lock: Option<File>,
+    /// Whether other instance was running when the app started
+    pub other_instance: bool,
     pub sessions_state: Rc<RefCell<ListState>>,
     pub stats_state: Rc<RefCell<ListState>>,
+    pub averages_state: Rc<RefCell<ListState>>,
 }
 
 impl App {
-    /// Creates new [`App`]
-    pub fn new() -> Self {
-        Self {
+    /// Creates new [`App`] with given config
+    pub fn new(config: Config) -> Self {
+        let mut app = Self {
             term: Term::new(),
+            config,
             ..Default::default()
+        };
+        match storage::instance_lock() {
+            Ok(Some(lock)) => app.instance_lock = Some(lock),
+            Ok(None) => app.other_instance = true,
+            Err(_) => {}
         }
+        app.load_stats();
+        app
     }
 
-    /// Creates new [`App`] and opens given session
-    pub fn open(session: String) -> Self {
-        let stats = Stats::load();
-        let Some(session_info) = stats.get_session(&session) else {
-            return Self::default();
+    /// Creates new [`App`] with given config and opens given session
+    pub fn open(session: String, config: Config) -> Self {
+        let mut app = Self::new(config);
+        let Some(session_info) = app.stats.get_session(&session) else {
+            return app;
         };
 
         let mut scramble = Scramble::new(&session_info.scramble_type);
         scramble.generate();
 
-        Self {
-            session: Some(session),
-            screen: Screen::Timer,
-            scramble: Some(scramble),
-            stats,
-            term: Term::new(),
-            ..Default::default()
-        }
+        app.stats.mark_used(&session);
+        _ = app.stats.save();
+
+        app.session = Some(session);
+        app.screen = Screen::Timer;
+        app.scramble = Some(scramble);
+        app
     }
 
     /// Runs the [`App`]
@@ -96,14 +122,31 @@ impl App {
         loop {
             if poll(Duration::from_millis(100))? {
                 self.key_listener()?;
+            } else if self.pb_flash.is_some()
+                && matches!(self.screen, Screen::Timer)
+            {
+                self.render()?;
             }
         }
     }
 
     /// Renders current screen of the [`App`]
     pub fn render(&mut self) -> Result<(), Error> {
+        // Session might have been removed by other instance
+        let removed =
+            self.session.as_ref().is_some_and(|s| !self.stats.exists(s));
+        if removed && !matches!(self.screen, Screen::LoadError) {
+            self.session = None;
+            self.scramble = None;
+            self.screen = Screen::Sessions;
+        }
+
         match self.screen {
             Screen::Timer => self.render_timer(),
+            Screen::Averages => self.render_averages(),
+            Screen::Detail => self.render_detail(),
+            Screen::Comment => self.render_timer(),
+            Screen::LoadError => self.render_load_error(),
             Screen::Sessions => self.render_sessions(),
         }
     }
@@ -116,10 +159,39 @@ impl App {
 
         match self.screen {
             Screen::Timer => self.listen_timer(code),
+            Screen::Averages => self.listen_averages(code),
+            Screen::Detail => self.listen_detail(code),
+            Screen::Comment => self.listen_comment(code),
+            Screen::LoadError => self.listen_load_error(code),
             Screen::Sessions => self.listen_sessions(code),
         }
     }
 
+    /// Loads stats, opens error screen when they fail to load
+    fn load_stats(&mut self) {
+        match Stats::load(&self.config) {
+            Ok(stats) => self.stats = stats,
+            Err(e) => {
+                self.stats = Stats::locked();
+                self.load_error = Some(e.to_string());
+                self.screen = Screen::LoadError;
+            }
+        }
+    }
+
+    /// Gets warning shown when other instance of the app is running
+    pub fn instance_warning(&self) -> Option<Span> {
+        if !self.other_instance && !self.stats.merged() {
+            return None;
+        }
+        Some(
+            "Other rsTimer instance is running, changes of both are merged \
+            when saving"
+                .fg(Color::Yellow)
+                .align(TextAlign::Center),
+        )
+    }
+
     fn _small_screen() -> Layout {
         let mut layout = Layout::vertical().center();
         layout.add_child(
@@ -140,14 +212,21 @@ impl Default for App {
     fn default() -> Self {
         Self {
             term: Default::default(),
-            config: Config::load(),
+            config: Default::default(),
             screen: Default::default(),
             session: None,
             scramble: None,
-            stats: Stats::load(),
+            stats: Stats::default(),
             time: Duration::new(0, 0),
+            pb_flash: None,
+            input: String::new(),
+            session_dialog: None,
+            load_error: None,
+            instance_lock: None,
+            other_instance: false,
             sessions_state: Rc::new(RefCell::new(ListState::selected(0, 0))),
             stats_state: Rc::new(RefCell::new(ListState::selected(0, 0))),
+            averages_state: Rc::new(RefCell::new(ListState::selected(0, 0))),
         }
     }
 }
@@ -1,16 +1,30 @@
+use std::path::PathBuf;
+
 use termint::{
     enums::Color,
     help,
     widgets::{Grad, StrSpanExtension},
 };
 
-use crate::error::Error;
+use crate::{
+    error::Error,
+    scramble::Output as ScrambleOutput,
+    stats::overview::Output,
+    transfer::{csv::Columns, Collision, Format},
+};
 
 #[derive(Debug)]
 pub enum Action {
     Add,
     Help,
     List,
+    Backups,
+    Restore(usize),
+    Migrate,
+    Import,
+    Export,
+    Stats,
+    Scramble,
 }
 
 /// Parses given arguments and checks for arguments conditions
@@ -18,6 +32,18 @@ pub enum Action {
 pub struct Args {
     pub action: Option<Action>,
     pub session: Option<String>,
+    pub dry_run: bool,
+    pub data_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub collision: Collision,
+    pub columns: Columns,
+    pub file: Option<PathBuf>,
+    pub output: Output,
+    pub sub: Vec<f64>,
+    pub scramble_type: Option<String>,
+    pub scramble_output: ScrambleOutput,
+    pub count: Option<usize>,
+    pub seed: Option<u64>,
 }
 
 impl Args {
@@ -25,21 +51,124 @@ impl Args {
     pub fn parse(args: std::env::Args) -> Result<Args, Error> {
         let mut parsed = Self::default();
 
-        if args.len() > 2 {
-            return Err(Error::Msg("Invalid number of arguments".to_string()));
-        }
-
+        // Meaning of the format depends on the action, which can follow it
+        let mut format = None;
         let mut args_iter = args.into_iter();
         args_iter.next();
-        for arg in args_iter {
+        while let Some(arg) = args_iter.next() {
             match arg.as_str() {
                 "-h" | "--help" => parsed.set_action(Action::Help)?,
                 "-a" | "--add" => parsed.set_action(Action::Add)?,
                 "-l" | "--list" => parsed.set_action(Action::List)?,
-                name => parsed.set_session(name)?,
+                "-b" | "--backups" => parsed.set_action(Action::Backups)?,
+                "-r" | "--restore" => {
+                    let index = args_iter
+                        .next()
+                        .and_then(|i| i.parse().ok())
+                        .ok_or(Error::Msg(
+                            "restore expects backup number".to_string(),
+                        ))?;
+                    parsed.set_action(Action::Restore(index))?
+                }
+                "-m" | "--migrate" => parsed.set_action(Action::Migrate)?,
+                "--dry-run" => parsed.dry_run = true,
+                "import" | "export" | "stats" | "scramble"
+                    if parsed.action.is_none() && parsed.session.is_none() =>
+                {
+                    parsed.set_action(match arg.as_str() {
+                        "import" => Action::Import,
+                        "export" => Action::Export,
+                        "stats" => Action::Stats,
+                        _ => Action::Scramble,
+                    })?
+                }
+                "-f" | "--format" => {
+                    format = Some(args_iter.next().ok_or(Error::Msg(
+                        "format expects format name".to_string(),
+                    ))?);
+                }
+                "--sub" => {
+                    let sub = args_iter.next().ok_or(Error::Msg(
+                        "sub expects list of seconds".to_string(),
+                    ))?;
+                    parsed.sub = sub
+                        .split(',')
+                        .map(|s| s.trim().parse().ok().filter(|s| *s > 0.))
+                        .collect::<Option<_>>()
+                        .ok_or(Error::Msg(format!("invalid sub '{sub}'")))?;
+                }
+                "-c" | "--columns" => {
+                    let columns = args_iter.next().ok_or(Error::Msg(
+                        "columns expects column mapping".to_string(),
+                    ))?;
+                    parsed.columns = columns.parse()?;
+                }
+                "--merge" => parsed.collision = Collision::Merge,
+                "--rename" => parsed.collision = Collision::Rename,
+                "-s" | "--session" => {
+                    let name = args_iter.next().ok_or(Error::Msg(
+                        "session expects session name".to_string(),
+                    ))?;
+                    parsed.set_session(&name)?;
+                }
+                "-n" | "--count" => {
+                    let count = args_iter
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|n| *n > 0)
+                        .ok_or(Error::Msg(
+                            "count expects positive number".to_string(),
+                        ))?;
+                    parsed.count = Some(count);
+                }
+                "--seed" => {
+                    let seed =
+                        args_iter.next().and_then(|s| s.parse().ok()).ok_or(
+                            Error::Msg("seed expects number".to_string()),
+                        )?;
+                    parsed.seed = Some(seed);
+                }
+                "-d" | "--data-dir" => {
+                    let dir = args_iter.next().ok_or(Error::Msg(
+                        "data dir expects directory".to_string(),
+                    ))?;
+                    parsed.data_dir = Some(PathBuf::from(dir));
+                }
+                name => match parsed.action {
+                    Some(Action::Import | Action::Export) => {
+                        parsed.set_file(name)?
+                    }
+                    Some(Action::Scramble) => {
+                        parsed.set_scramble_type(name)?
+                    }
+                    _ => parsed.set_session(name)?,
+                },
             }
         }
+        if let Some(format) = format {
+            parsed.set_format(&format)?;
+        }
 
+        if matches!(parsed.action, Some(Action::Import | Action::Export)) {
+            if parsed.format.is_none() {
+                return Err(Error::Msg("format not provided".to_string()));
+            }
+            if parsed.file.is_none() {
+                return Err(Error::Msg("file not provided".to_string()));
+            }
+        }
+
+        if matches!(parsed.action, Some(Action::Scramble))
+            && parsed.scramble_type.is_none()
+        {
+            return Err(Error::Msg("scramble type not provided".to_string()));
+        }
+
+        if parsed.dry_run && !matches!(parsed.action, Some(Action::Migrate)) {
+            return Err(Error::Msg(
+                "dry run can be used only with migrate".to_string(),
+            ));
+        }
         Ok(parsed)
     }
 
@@ -55,10 +184,42 @@ impl Args {
             "rstimer" => "Opens session picker to choose which one to open\n"
             "rstimer" ["session name"] => "Opens timer with given session\n"
             "rstimer" ["options"] => "Behaves according to options\n"
+            "rstimer import" ["options"] ["file"] =>
+                "Imports sessions from given file (- for stdin)\n"
+            "rstimer export" ["options"] ["file"] =>
+                "Exports sessions to given file (- for stdout)\n"
+            "rstimer stats" ["options"] ["session name"] =>
+                "Prints statistics of given session or all sessions\n"
+            "rstimer scramble" ["options"] ["scramble type"] =>
+                "Prints scrambles of given type\n"
             "Options":
             "-a  --add" => "Opens dialog to add new session\n"
             "-l  --list" => "Lists all sessions\n"
-            "-h  --help" => "Prints this help"
+            "-b  --backups" => "Lists stats backups\n"
+            "-r  --restore" ["number"] => "Restores stats from given backup\n"
+            "-m  --migrate" => "Migrates stats and config to current version\n"
+            "--dry-run" => "With migrate, only reports what would change\n"
+            "-d  --data-dir" ["directory"] =>
+                "Uses given directory for config and stats\n"
+            "-h  --help" => "Prints this help\n"
+            "Import and export options":
+            "-f  --format" ["format"] => "File format: cstimer, csv, twisty\n"
+            "-s  --session" ["name"] =>
+                "Exports only given session, imports all to given session\n"
+            "-c  --columns" ["field=header,..."] =>
+                "Maps fields to the CSV columns on import\n"
+            "--merge" =>
+                "Imports to existing session with the same name (default)\n"
+            "--rename" => "Imports to new session when the name is used\n"
+            "Stats options":
+            "-f  --format" ["format"] => "Output format: text, json\n"
+            "--sub" ["seconds,..."] =>
+                "Counts solves faster than given times (default near mean)\n"
+            "Scramble options":
+            "-f  --format" ["format"] =>
+                "Output format: plain, numbered, json\n"
+            "-n  --count" ["count"] => "Number of scrambles (default 1)\n"
+            "--seed" ["number"] => "Seed giving the same scrambles each time"
         );
     }
 
@@ -72,6 +233,36 @@ impl Args {
         }
     }
 
+    /// Parses given format as the output format of the action
+    fn set_format(&mut self, format: &str) -> Result<(), Error> {
+        match self.action {
+            Some(Action::Stats) => self.output = format.parse()?,
+            Some(Action::Scramble) => self.scramble_output = format.parse()?,
+            _ => self.format = Some(format.parse()?),
+        }
+        Ok(())
+    }
+
+    /// Sets file to given value when is not set already
+    fn set_file(&mut self, file: &str) -> Result<(), Error> {
+        if self.file.is_some() {
+            Err(Error::Msg("multiple files provided".to_string()))
+        } else {
+            self.file = Some(PathBuf::from(file));
+            Ok(())
+        }
+    }
+
+    /// Sets scramble type to given value when is not set already
+    fn set_scramble_type(&mut self, name: &str) -> Result<(), Error> {
+        if self.scramble_type.is_some() {
+            Err(Error::Msg("multiple scramble types provided".to_string()))
+        } else {
+            self.scramble_type = Some(name.to_string());
+            Ok(())
+        }
+    }
+
     /// Sets session name to given value when is not set already
     fn set_session(&mut self, name: &str) -> Result<(), Error> {
         if self.session.is_some() {
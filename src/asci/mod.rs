@@ -27,9 +27,10 @@ pub fn get_time(
     let number = format!("{:.1$}", num, decimals);
     let mut res = String::new();
 
+    let chars: Vec<_> = number.chars().map(|d| &digits[&d]).collect();
     for i in 0..height {
-        for digit in number.chars() {
-            res.push_str(digits[&digit][i]);
+        for digit in chars.iter() {
+            res.push_str(digit[i]);
         }
     }
     (res, height)
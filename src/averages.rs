@@ -0,0 +1,134 @@
+use crossterm::event::KeyCode;
+use termint::{
+    enums::Color,
+    geometry::Constraint,
+    style::Style,
+    widgets::{Block, Layout, List, Spacer},
+};
+
+use crate::{
+    app::{App, Screen},
+    error::Error,
+    stats::average::Average,
+    widgets::raw_span::RawSpan,
+};
+
+/// Session averages settings implementation
+impl App {
+    /// Renders averages picker of the current session
+    pub fn render_averages(&mut self) -> Result<(), Error> {
+        let shown = self.shown_averages();
+        let items: Vec<String> = self
+            .average_options()
+            .iter()
+            .map(|a| {
+                let check = if shown.contains(a) { 'x' } else { ' ' };
+                format!("[{check}] {a}")
+            })
+            .collect();
+
+        let mut block = Block::vertical().title("Averages").center();
+        block.add_child(
+            List::new(items, self.averages_state.clone())
+                .selected_style(Style::new().fg(Color::Cyan))
+                .auto_scroll(),
+            Constraint::Fill,
+        );
+
+        let mut center = Layout::horizontal().center();
+        center.add_child(block, Constraint::Length(16));
+
+        let mut layout = Layout::vertical();
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(center, Constraint::Length(10));
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(self.averages_help(), Constraint::Length(1));
+
+        self.term.render(layout)?;
+        Ok(())
+    }
+
+    /// Listens to pressed keys while showing Averages screen
+    pub fn listen_averages(&mut self, code: KeyCode) -> Result<(), Error> {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => {
+                let mut state = self.averages_state.borrow_mut();
+                if let Some(sel) = state.selected {
+                    state.selected = Some(sel.saturating_sub(1));
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => {
+                let len = self.average_options().len();
+                let mut state = self.averages_state.borrow_mut();
+                if let Some(sel) = state.selected {
+                    if sel + 1 < len {
+                        state.selected = Some(sel + 1);
+                    }
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(sel) = self.averages_state.borrow().selected else {
+                    return Ok(());
+                };
+                let Some(average) = self.average_options().get(sel).copied()
+                else {
+                    return Ok(());
+                };
+
+                let session = self.session.as_ref().unwrap();
+                if let Some(session) = self.stats.get_session_mut(session) {
+                    session.toggle_average(average);
+                    self.stats.save()?;
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                self.screen = Screen::Timer;
+            }
+            _ => return Ok(()),
+        }
+        self.render()
+    }
+
+    /// Gets averages currently shown in the session
+    fn shown_averages(&self) -> Vec<Average> {
+        self.session
+            .as_ref()
+            .and_then(|s| self.stats.get_session(s))
+            .map(|s| s.averages.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets all averages that can be toggled - presets and averages set
+    /// manually in the session
+    fn average_options(&self) -> Vec<Average> {
+        let mut options = Average::PRESETS.to_vec();
+        for average in self.shown_averages() {
+            if !options.contains(&average) {
+                options.push(average);
+            }
+        }
+        options
+    }
+
+    /// Gets averages help layout
+    fn averages_help(&self) -> Layout {
+        let mut layout = Layout::horizontal();
+        layout.add_child(
+            RawSpan::new("[↑|k]Move up ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[↓/j]Move down ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[Enter|Space]Toggle ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[Esc|q]Back ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout
+    }
+}
@@ -0,0 +1,72 @@
+use crossterm::event::KeyCode;
+use termint::{
+    enums::Color,
+    geometry::Constraint,
+    widgets::{Layout, StrSpanExtension},
+};
+
+use crate::{
+    app::{App, Screen},
+    error::Error,
+    widgets::raw_span::RawSpan,
+};
+
+/// Solve comment editing implementation
+impl App {
+    /// Opens comment input with comment of the currently selected solve
+    pub fn edit_comment(&mut self) {
+        let sel = self.stats_state.borrow().selected.unwrap_or_default();
+        let Some(stat) = self
+            .session
+            .as_ref()
+            .and_then(|s| self.stats.get_session(s))
+            .and_then(|s| s.stats.get(sel))
+        else {
+            return;
+        };
+
+        self.input = stat.comment().to_owned();
+        self.screen = Screen::Comment;
+    }
+
+    /// Listens to pressed keys while editing comment
+    pub fn listen_comment(&mut self, code: KeyCode) -> Result<(), Error> {
+        match code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => _ = self.input.pop(),
+            KeyCode::Enter => {
+                let sel =
+                    self.stats_state.borrow().selected.unwrap_or_default();
+                let comment = std::mem::take(&mut self.input);
+                self.stats.set_comment(
+                    sel,
+                    self.session.as_ref().unwrap(),
+                    comment.trim().to_owned(),
+                );
+                self.stats.save()?;
+                self.screen = Screen::Timer;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.screen = Screen::Timer;
+            }
+            _ => return Ok(()),
+        }
+        self.render()
+    }
+
+    /// Gets comment input layout
+    pub fn comment_input(&self) -> Layout {
+        let mut layout = Layout::horizontal();
+        layout.add_child("Comment: ".fg(Color::Cyan), Constraint::Min(0));
+        layout.add_child(
+            RawSpan::new(format!("{}█", self.input)),
+            Constraint::Fill,
+        );
+        layout.add_child(
+            RawSpan::new(" [Enter]Save [Esc]Cancel").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout
+    }
+}
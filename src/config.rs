@@ -1,43 +1,63 @@
-use std::{
-    fs::{create_dir_all, read_to_string, File},
-    io::Write,
-    path::PathBuf,
-};
+use std::fs::{create_dir_all, read_to_string};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{asci::digit_type::DigitType, error::Error};
+use crate::{
+    asci::digit_type::DigitType,
+    error::Error,
+    paths,
+    stats::session_sort::SessionSort,
+    storage::{
+        migrate::{self, Format},
+        write_atomic, Backend,
+    },
+};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub font: DigitType,
+    #[serde(default)]
+    pub session_sort: SessionSort,
+    /// Number of stats file backups to keep
+    #[serde(default = "Config::default_backups")]
+    pub backups: usize,
+    /// Storage backend of the stats
+    #[serde(default)]
+    pub storage: Backend,
 }
 
 impl Config {
     /// Loads config, uses default config when not found
-    pub fn load() -> Config {
-        let mut dir = Config::get_path();
+    ///
+    /// **Returns:**
+    /// * Loaded config, error when it can't be migrated (e.g. it was saved
+    ///   by newer version of rsTimer), so it's never overwritten
+    pub fn load() -> Result<Config, Error> {
+        let Ok(mut dir) = paths::config_dir() else {
+            return Ok(Config::default());
+        };
         dir.push("config.json");
 
-        match serde_json::from_str::<Self>(
-            &read_to_string(dir).unwrap_or_default(),
-        ) {
-            Ok(conf) => conf,
-            Err(_) => Self::default(),
-        }
+        let Ok(mut value) =
+            serde_json::from_str(&read_to_string(dir).unwrap_or_default())
+        else {
+            return Ok(Config::default());
+        };
+        migrate::migrate(&mut value, Format::Config)?;
+        Ok(serde_json::from_value(value).unwrap_or_default())
     }
 
     /// Saves config to the JSON file
     pub fn save(&self) -> Result<(), Error> {
-        let mut dir = Config::get_path();
+        let mut dir = paths::config_dir()?;
         create_dir_all(&dir)?;
 
         dir.push("config.json");
-        let mut file = File::create(&dir)?;
 
-        let json_string = serde_json::to_string_pretty(self)?;
-        file.write_all(json_string.as_bytes())?;
-        Ok(())
+        let mut value = serde_json::to_value(self)?;
+        migrate::set_version(&mut value, Format::Config);
+        let json_string = serde_json::to_string_pretty(&value)?;
+        write_atomic(&dir, json_string.as_bytes())
     }
 
     /// Sets font to the given value and saves the config
@@ -46,10 +66,28 @@ impl Config {
         self.save()
     }
 
-    /// Gets path of the config folder
-    fn get_path() -> PathBuf {
-        let mut path = dirs::config_dir().unwrap_or(PathBuf::from("."));
-        path.push("rstimer");
-        path
+    /// Sets session sort to the given value and saves the config
+    pub fn set_session_sort(
+        &mut self,
+        sort: SessionSort,
+    ) -> Result<(), Error> {
+        self.session_sort = sort;
+        self.save()
+    }
+
+    /// Gets default number of stats backups
+    fn default_backups() -> usize {
+        5
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            font: Default::default(),
+            session_sort: Default::default(),
+            backups: Config::default_backups(),
+            storage: Default::default(),
+        }
     }
 }
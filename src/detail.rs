@@ -0,0 +1,106 @@
+use crossterm::event::KeyCode;
+use termint::{
+    enums::Color,
+    geometry::Constraint,
+    widgets::{Block, Layout, Spacer, StrSpanExtension},
+};
+
+use crate::{
+    app::{App, Screen},
+    error::Error,
+    widgets::raw_span::RawSpan,
+};
+
+/// Solve detail implementation
+impl App {
+    /// Renders detail of the currently selected solve
+    pub fn render_detail(&mut self) -> Result<(), Error> {
+        let mut layout = Layout::vertical();
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(self.detail_block(), Constraint::Min(0));
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(self.detail_help(), Constraint::Length(1));
+
+        self.term.render(layout)?;
+        Ok(())
+    }
+
+    /// Listens to pressed keys while showing solve detail
+    pub fn listen_detail(&mut self, code: KeyCode) -> Result<(), Error> {
+        match code {
+            KeyCode::Enter
+            | KeyCode::Esc
+            | KeyCode::Char('q')
+            | KeyCode::Char('Q') => {
+                self.screen = Screen::Timer;
+                self.render()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets block containing the solve detail
+    fn detail_block(&self) -> Layout {
+        let mut center = Layout::horizontal().center();
+        let sel = self.stats_state.borrow().selected.unwrap_or_default();
+        let Some(session) = self
+            .session
+            .as_ref()
+            .and_then(|s| self.stats.get_session(s))
+        else {
+            return center;
+        };
+        let Some(stat) = session.stats.get(sel) else {
+            center.add_child("No solve selected...", Constraint::Min(0));
+            return center;
+        };
+
+        let mut block = Block::vertical()
+            .title(format!("Solve {}", session.stats.len() - sel).as_str());
+        block.add_child(
+            format!("Time: {}", stat.solve_time()),
+            Constraint::Min(0),
+        );
+        block.add_child(
+            format!("Raw time: {:.3}", stat.time.as_secs_f64()),
+            Constraint::Min(0),
+        );
+        block.add_child(
+            format!("Penalty: {}", stat.penalty),
+            Constraint::Min(0),
+        );
+        block.add_child(
+            format!("Date: {}", stat.datetime.format("%Y-%m-%d %H:%M:%S")),
+            Constraint::Min(0),
+        );
+        for average in session.averages.iter() {
+            if let Some(value) = session.average_at(sel, *average) {
+                block.add_child(
+                    format!("{average}: {value}"),
+                    Constraint::Min(0),
+                );
+            }
+        }
+        block.add_child(Spacer::new(), Constraint::Length(1));
+        block.add_child("Scramble:".fg(Color::Gray), Constraint::Min(0));
+        block.add_child(stat.scramble(), Constraint::Min(0));
+        if !stat.comment().is_empty() {
+            block.add_child(Spacer::new(), Constraint::Length(1));
+            block.add_child("Comment:".fg(Color::Gray), Constraint::Min(0));
+            block.add_child(stat.comment(), Constraint::Min(0));
+        }
+
+        center.add_child(block, Constraint::Length(60));
+        center
+    }
+
+    /// Gets solve detail help layout
+    fn detail_help(&self) -> Layout {
+        let mut layout = Layout::horizontal();
+        layout.add_child(
+            RawSpan::new("[Enter|Esc|q]Back ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout
+    }
+}
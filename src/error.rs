@@ -1,10 +1,15 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 
 #[derive(Debug)]
 pub enum Error {
     IO(std::io::Error),
     Serde(serde_json::Error),
+    Csv(csv::Error),
     Msg(String),
+    /// Stats file couldn't be parsed and was moved to the given backup
+    CorruptStats(PathBuf, serde_json::Error),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
     Exit,
 }
 
@@ -20,6 +25,19 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Sqlite(value)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(value: csv::Error) -> Self {
+        Self::Csv(value)
+    }
+}
+
 impl From<&str> for Error {
     fn from(value: &str) -> Self {
         Self::Msg(value.to_string())
@@ -31,7 +49,15 @@ impl Display for Error {
         match self {
             Error::IO(e) => write!(f, "{e}"),
             Error::Serde(e) => write!(f, "{e}"),
+            Error::Csv(e) => write!(f, "{e}"),
             Error::Msg(msg) => write!(f, "{msg}"),
+            Error::CorruptStats(backup, e) => write!(
+                f,
+                "stats file is corrupt ({e}), it was moved to {}",
+                backup.display()
+            ),
+            #[cfg(feature = "sqlite")]
+            Error::Sqlite(e) => write!(f, "{e}"),
             Error::Exit => write!(f, "exit"),
         }
     }
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent};
+use termint::{
+    enums::Color,
+    geometry::Constraint,
+    widgets::{Layout, Spacer, Span, StrSpanExtension},
+};
+
+use crate::{app::App, asci::time_layout, error::Error, stats::stat::Penalty};
+
+/// Length of the WCA inspection
+const INSPECTION: Duration = Duration::from_secs(15);
+/// Inspection time after which the solve is DNF
+const INSPECTION_DNF: Duration = Duration::from_secs(17);
+
+/// WCA inspection implementation
+impl App {
+    /// Starts the inspection loop
+    ///
+    /// **Returns:**
+    /// * Penalty the solve gets for the inspection time, [`None`] when the
+    ///   inspection was cancelled
+    pub fn start_inspection(&mut self) -> Result<Option<Penalty>, Error> {
+        let start = Instant::now();
+
+        let wait_time = Duration::from_millis(100);
+        let mut last = start;
+
+        self._render_inspection(Duration::ZERO)?;
+        loop {
+            if last.elapsed() >= wait_time {
+                self._render_inspection(start.elapsed())?;
+                last = Instant::now();
+            }
+            if poll(Duration::from_millis(1))? {
+                let Event::Key(KeyEvent { code, .. }) = read()? else {
+                    continue;
+                };
+
+                match code {
+                    KeyCode::Char(' ') => {
+                        return Ok(Some(Self::inspection_penalty(
+                            start.elapsed(),
+                        )))
+                    }
+                    KeyCode::Esc => return Ok(None),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Helper function for rendering inspection
+    fn _render_inspection(&mut self, elapsed: Duration) -> Result<(), Error> {
+        let remaining = INSPECTION.saturating_sub(elapsed);
+        let (time, height) =
+            time_layout(remaining.as_secs_f64().ceil(), 0, &self.config.font);
+
+        let mut status = Layout::horizontal().center();
+        status.add_child(Self::inspection_status(elapsed), Constraint::Min(0));
+
+        let mut timer = Layout::vertical();
+        timer.add_child(Spacer::new(), Constraint::Fill);
+        timer.add_child(time, Constraint::Length(height));
+        timer.add_child(Spacer::new(), Constraint::Length(1));
+        timer.add_child(status, Constraint::Min(0));
+        timer.add_child(Spacer::new(), Constraint::Fill);
+
+        self.render_timer_screen(timer)
+    }
+
+    /// Gets inspection status text, containing the WCA warnings
+    fn inspection_status(elapsed: Duration) -> Span {
+        match elapsed.as_secs() {
+            s if s >= INSPECTION_DNF.as_secs() => "DNF".fg(Color::Red),
+            s if s >= INSPECTION.as_secs() => "+2".fg(Color::Red),
+            12.. => "12s".fg(Color::Red),
+            8.. => "8s".fg(Color::Yellow),
+            _ => "Inspection".fg(Color::Gray),
+        }
+    }
+
+    /// Gets penalty based on the given inspection time
+    fn inspection_penalty(elapsed: Duration) -> Penalty {
+        if elapsed > INSPECTION_DNF {
+            Penalty::Dnf
+        } else if elapsed > INSPECTION {
+            Penalty::PlusTwo
+        } else {
+            Penalty::None
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crossterm::event::KeyCode;
+use termint::{
+    enums::{Color, Modifier},
+    geometry::Constraint,
+    widgets::{Block, Layout, Spacer, Span, StrSpanExtension},
+};
+
+use crate::{
+    app::{App, Screen},
+    error::Error,
+    widgets::raw_span::RawSpan,
+};
+
+/// Stats load error screen implementation
+impl App {
+    /// Renders error screen shown when stats fail to load
+    pub fn render_load_error(&mut self) -> Result<(), Error> {
+        let mut block = Block::vertical().title("Stats failed to load");
+        block.add_child(
+            Span::new(self.load_error.clone().unwrap_or_default())
+                .fg(Color::Red),
+            Constraint::Min(0),
+        );
+        block.add_child(Spacer::new(), Constraint::Length(1));
+        block.add_child(
+            "Your stats won't be saved until you confirm starting with \
+            empty stats."
+                .modifier(Modifier::BOLD),
+            Constraint::Min(0),
+        );
+
+        let mut center = Layout::horizontal().center();
+        center.add_child(block, Constraint::Length(60));
+
+        let mut layout = Layout::vertical();
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(center, Constraint::Min(0));
+        layout.add_child(Spacer::new(), Constraint::Fill);
+        layout.add_child(self.load_error_help(), Constraint::Length(1));
+
+        self.term.render(layout)?;
+        Ok(())
+    }
+
+    /// Listens to pressed keys while showing stats load error
+    pub fn listen_load_error(&mut self, code: KeyCode) -> Result<(), Error> {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.stats.unlock();
+                self.load_error = None;
+                self.screen = Screen::Sessions;
+                self.render()
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                Err(Error::Exit)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets stats load error help layout
+    fn load_error_help(&self) -> Layout {
+        let mut layout = Layout::horizontal();
+        layout.add_child(
+            RawSpan::new("[y]Start with empty stats ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[Esc|q]Quit ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout
+    }
+}
@@ -1,35 +1,64 @@
 use std::{
     env::args,
-    io::{stdin, stdout, Write},
+    fs::{read_to_string, write},
+    io::{stdin, stdout, Read, Write},
+    process::exit,
 };
 
 use app::App;
 use args::Action;
+use chrono::{DateTime, Local};
 use error::Error;
+use rand::{rngs::StdRng, SeedableRng};
+use scramble::{Output as ScrambleOutput, Scramble};
 
-use crate::{args::Args, stats::stats::Stats};
+use crate::{
+    args::Args,
+    config::Config,
+    stats::{
+        overview::{Output, Overview},
+        session_sort::SessionSort,
+        stats::Stats,
+    },
+    storage::migrate::{migrate_file, Format},
+    transfer::Options,
+};
 
 mod app;
 mod args;
 mod asci;
+mod averages;
+mod comment;
 mod config;
+mod detail;
 mod error;
+mod inspection;
+mod load_error;
+mod paths;
 mod scramble;
+mod session_dialog;
 mod sessions;
 mod stats;
+mod storage;
 mod timer;
+mod transfer;
 mod widgets;
 
 fn main() -> Result<(), Error> {
     let args = match Args::parse(args()) {
         Ok(args) => args,
-        Err(_) => {
-            println!("Error parsing arguments");
+        Err(e) => {
+            println!("Error parsing arguments: {e}");
             return Ok(());
         }
     };
 
-    run(args)
+    paths::init(args.data_dir.clone());
+    if let Err(e) = run(args) {
+        eprintln!("Error: {e}");
+        exit(1);
+    }
+    Ok(())
 }
 
 /// Runs the app based on arguments
@@ -38,6 +67,13 @@ fn run(args: Args) -> Result<(), Error> {
         Some(Action::Add) => add_session()?,
         Some(Action::Help) => Args::help(),
         Some(Action::List) => list_sessions(),
+        Some(Action::Backups) => list_backups()?,
+        Some(Action::Restore(index)) => restore_backup(index)?,
+        Some(Action::Migrate) => migrate(args.dry_run)?,
+        Some(Action::Import) => import(args)?,
+        Some(Action::Export) => export(args)?,
+        Some(Action::Stats) => print_stats(args)?,
+        Some(Action::Scramble) => print_scrambles(args)?,
         None => run_timer(args.session)?,
     }
     Ok(())
@@ -45,9 +81,10 @@ fn run(args: Args) -> Result<(), Error> {
 
 /// Starts app - if session is None, it opens session picker
 fn run_timer(session: Option<String>) -> Result<(), Error> {
+    let config = Config::load()?;
     let mut app = match session {
-        Some(session) => App::open(session),
-        None => App::new(),
+        Some(session) => App::open(session, config),
+        None => App::new(config),
     };
     app.run()
 }
@@ -65,7 +102,16 @@ fn add_session() -> Result<(), Error> {
     let mut scramble_type = String::new();
     stdin().read_line(&mut scramble_type)?;
 
-    let mut stats = Stats::load();
+    let mut stats = match Stats::load(&Config::load()?) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            if !confirm("Start with empty stats?")? {
+                return Ok(());
+            }
+            Stats::default()
+        }
+    };
     stats.add_session(name.trim(), scramble_type.trim())?;
 
     stats.save()?;
@@ -75,8 +121,238 @@ fn add_session() -> Result<(), Error> {
 
 /// Lists all sessions
 fn list_sessions() {
-    let stats = Stats::load();
-    stats.print_sessions();
+    match Config::load().and_then(|c| Stats::load(&c)) {
+        Ok(stats) => stats.print_sessions(),
+        Err(e) => eprintln!("Error: {e}"),
+    }
+}
+
+/// Prints statistics of the given session or of all the sessions
+fn print_stats(args: Args) -> Result<(), Error> {
+    let stats = Stats::load(&Config::load()?)?;
+    let sessions = match &args.session {
+        Some(session) if !stats.exists(session) => {
+            return Err(Error::Msg(format!(
+                "session '{session}' doesn't exist"
+            )));
+        }
+        Some(session) => vec![session.clone()],
+        None => stats.get_sessions(SessionSort::Custom),
+    };
+    let overviews: Vec<_> = sessions
+        .iter()
+        .filter_map(|s| Overview::new(&stats, s, &args.sub))
+        .collect();
+
+    match args.output {
+        Output::Text => {
+            for (i, overview) in overviews.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                overview.print();
+            }
+        }
+        Output::Json => {
+            let mut values: Vec<_> =
+                overviews.iter().map(Overview::to_json).collect();
+            let value = match args.session {
+                Some(_) => values.pop().unwrap_or_default(),
+                None => serde_json::Value::Array(values),
+            };
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+    }
+    Ok(())
+}
+
+/// Prints scrambles of the given type
+fn print_scrambles(args: Args) -> Result<(), Error> {
+    let scramble_type = args.scramble_type.unwrap_or_default();
+    let mut scramble = Scramble::try_new(&scramble_type)?;
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let count = args.count.unwrap_or(1);
+    let scrambles: Vec<String> = (0..count)
+        .map(|_| {
+            scramble.generate_with(&mut rng);
+            scramble.get().to_owned()
+        })
+        .collect();
+
+    match args.scramble_output {
+        ScrambleOutput::Plain => {
+            scrambles.iter().for_each(|s| println!("{s}"));
+        }
+        ScrambleOutput::Numbered => {
+            let width = count.to_string().len();
+            for (i, s) in scrambles.iter().enumerate() {
+                println!("{:>width$}. {s}", i + 1);
+            }
+        }
+        ScrambleOutput::Json => {
+            let value = serde_json::json!({
+                "scramble_type": scramble_type,
+                "seed": args.seed,
+                "scrambles": scrambles,
+            });
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+    }
+    Ok(())
+}
+
+/// Lists all stats backups
+fn list_backups() -> Result<(), Error> {
+    let backups = Stats::backups(&Config::load()?)?;
+    if backups.is_empty() {
+        println!("No backups found");
+        return Ok(());
+    }
+
+    println!("\x1b[92mBackups:");
+    for backup in backups {
+        let modified = backup
+            .modified
+            .map(|m| DateTime::<Local>::from(m).format("%Y-%m-%d %H:%M:%S"))
+            .map(|m| m.to_string())
+            .unwrap_or("Unknown".to_string());
+        println!(
+            "  \x1b[93m{}\x1b[0m {modified} ({})",
+            backup.index,
+            backup.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Restores stats from the backup with given index
+fn restore_backup(index: usize) -> Result<(), Error> {
+    if !confirm(&format!("Restore stats from backup {index}?"))? {
+        return Ok(());
+    }
+    Stats::restore(&Config::load()?, index)?;
+    println!("Stats restored from backup {index}");
+    Ok(())
+}
+
+/// Migrates stats and config files to the current version of their format
+fn migrate(dry_run: bool) -> Result<(), Error> {
+    let config = Config::load()?;
+    let files = [
+        (storage::stats_path()?, Format::Stats),
+        (paths::config_dir()?.join("config.json"), Format::Config),
+    ];
+    for (path, format) in files {
+        print!("\x1b[92m{}\x1b[0m ({}): ", format.name(), path.display());
+        let Some(report) =
+            migrate_file(&path, format, dry_run, config.backups)?
+        else {
+            println!("not found");
+            continue;
+        };
+
+        if report.from == report.to {
+            println!("up to date (version {})", report.to);
+            continue;
+        }
+        println!("version {} -> {}", report.from, report.to);
+        for change in report.changes {
+            println!("  \x1b[93m-\x1b[0m {change}");
+        }
+    }
+
+    if dry_run {
+        println!("Dry run, nothing was changed");
+    }
+    Ok(())
+}
+
+/// Imports sessions from the file given by the arguments
+fn import(args: Args) -> Result<(), Error> {
+    let (Some(format), Some(file)) = (args.format, args.file) else {
+        return Ok(());
+    };
+
+    let text = if file.as_os_str() == "-" {
+        let mut text = String::new();
+        stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        read_to_string(&file)?
+    };
+    let options = Options {
+        columns: args.columns,
+    };
+    let imported = format.read(&text, &options)?;
+    for warning in imported.warnings.iter() {
+        eprintln!("\x1b[93mWarning:\x1b[0m {warning}");
+    }
+
+    let mut stats = Stats::load(&Config::load()?)?;
+    let summaries = transfer::import(
+        &mut stats,
+        imported,
+        args.collision,
+        args.session.as_deref(),
+    )?;
+    stats.save()?;
+
+    for summary in summaries {
+        let target = if summary.created {
+            format!("new session {}", summary.session)
+        } else {
+            format!("session {}", summary.session)
+        };
+        print!(
+            "\x1b[92m{}\x1b[0m: {} solves imported to {target}",
+            summary.source, summary.added
+        );
+        if summary.skipped > 0 {
+            print!(" ({} already there)", summary.skipped);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// Exports sessions to the file given by the arguments
+fn export(args: Args) -> Result<(), Error> {
+    let (Some(format), Some(file)) = (args.format, args.file) else {
+        return Ok(());
+    };
+
+    let stats = Stats::load(&Config::load()?)?;
+    let sessions = match args.session {
+        Some(session) if !stats.exists(&session) => {
+            return Err(Error::Msg(format!(
+                "session '{session}' doesn't exist"
+            )))
+        }
+        Some(session) => vec![session],
+        None => stats.get_sessions(SessionSort::Custom),
+    };
+
+    let text = format.write(&stats, &sessions)?;
+    if file.as_os_str() == "-" {
+        print!("{text}");
+    } else {
+        write(&file, text)?;
+        println!("Exported {} sessions to {}", sessions.len(), file.display());
+    }
+    Ok(())
+}
+
+/// Asks user to confirm given question
+fn confirm(question: &str) -> Result<bool, Error> {
+    print!("{question} [y/N]: ");
+    stdout().flush()?;
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
 }
 
 // /// Prints error message to stderr
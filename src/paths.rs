@@ -0,0 +1,124 @@
+use std::{
+    env,
+    fs::{copy, create_dir_all, read_dir, remove_file, rename},
+    io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::error::Error;
+
+/// Environment variable setting the directory with config and stats
+const HOME_VAR: &str = "RSTIMER_HOME";
+
+/// Directory next to the binary, which enables portable mode when it exists
+const PORTABLE_DIR: &str = "rstimer-data";
+
+/// Resolved directories, error message when they couldn't be resolved
+static DIRS: OnceLock<Result<Dirs, String>> = OnceLock::new();
+
+/// Directories rsTimer stores its files in
+#[derive(Debug)]
+struct Dirs {
+    config: PathBuf,
+    data: PathBuf,
+}
+
+/// Resolves the directories. Has to be called before any of the paths is
+/// used, otherwise the given directory is ignored
+///
+/// **Parameters:**
+/// * `data_dir` - directory given by the `--data-dir` flag
+pub fn init(data_dir: Option<PathBuf>) {
+    _ = DIRS.set(resolve(data_dir));
+}
+
+/// Gets the directory to save config in
+pub fn config_dir() -> Result<PathBuf, Error> {
+    Ok(dirs()?.config.clone())
+}
+
+/// Gets the directory to save stats in
+pub fn data_dir() -> Result<PathBuf, Error> {
+    Ok(dirs()?.data.clone())
+}
+
+fn dirs() -> Result<&'static Dirs, Error> {
+    DIRS.get_or_init(|| resolve(None))
+        .as_ref()
+        .map_err(|e| Error::Msg(e.clone()))
+}
+
+/// Resolves the directories. Config and stats are stored in one directory
+/// given by the `--data-dir` flag, `RSTIMER_HOME` or in portable mode,
+/// otherwise they're stored in the user's config and data directories
+fn resolve(data_dir: Option<PathBuf>) -> Result<Dirs, String> {
+    let custom = data_dir
+        .or_else(|| {
+            env::var_os(HOME_VAR)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        })
+        .or_else(portable_dir);
+    if let Some(dir) = custom {
+        return Ok(Dirs {
+            config: dir.clone(),
+            data: dir,
+        });
+    }
+
+    let config = dirs::config_dir()
+        .ok_or(format!("can't find config directory, set {HOME_VAR}"))?
+        .join("rstimer");
+    let data = dirs::data_dir()
+        .ok_or(format!("can't find data directory, set {HOME_VAR}"))?
+        .join("rstimer");
+    move_stats(&config, &data).map_err(|e| {
+        format!(
+            "failed to move stats from {} to {}: {e}",
+            config.display(),
+            data.display()
+        )
+    })?;
+    Ok(Dirs { config, data })
+}
+
+/// Gets the portable directory, when it exists next to the binary
+fn portable_dir() -> Option<PathBuf> {
+    let dir = env::current_exe().ok()?.parent()?.join(PORTABLE_DIR);
+    dir.is_dir().then_some(dir)
+}
+
+/// Moves stats files from the config directory, where older versions
+/// stored them, to the data directory
+fn move_stats(old: &Path, new: &Path) -> io::Result<()> {
+    if old == new
+        || !old.is_dir()
+        || new.join("stats").exists()
+        || new.join("stats.db").exists()
+    {
+        return Ok(());
+    }
+
+    let mut files: Vec<_> = read_dir(old)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name())
+        .filter(|n| n.to_string_lossy().starts_with("stats"))
+        .collect();
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    // Stats are moved last, so an interrupted move is finished next time
+    files.sort_by_key(|n| n == "stats" || n == "stats.db");
+    create_dir_all(new)?;
+    for file in files {
+        let (from, to) = (old.join(&file), new.join(&file));
+        if rename(&from, &to).is_err() {
+            // Rename doesn't work across file systems
+            copy(&from, &to)?;
+            remove_file(&from)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use rand::Rng;
+
+/// Pins and dials turned in the order of the WCA notation, `y2` turns the
+/// clock to the back side
+const TURNS: [&str; 14] = [
+    "UR", "DR", "DL", "UL", "U", "R", "D", "L", "ALL", "U", "R", "D", "L",
+    "ALL",
+];
+
+/// Number of turns on the front side, followed by `y2` and the back side
+const FRONT: usize = 9;
+
+/// Pins, which can be left up at the end of the scramble
+const PINS: [&str; 4] = ["UR", "DR", "DL", "UL"];
+
+/// Generates Clock scramble in the WCA notation. Each turn is written as
+/// pins and amount of the dial turn (`UR3+` clockwise, `UR2-`
+/// counterclockwise), the scramble ends with pins left up
+pub fn generate(rng: &mut impl Rng) -> String {
+    let mut moves = Vec::with_capacity(TURNS.len() + PINS.len() + 1);
+    for (i, pins) in TURNS.iter().enumerate() {
+        if i == FRONT {
+            moves.push("y2".to_string());
+        }
+        moves.push(turn(pins, rng.gen_range(-5..=6)));
+    }
+
+    for pin in PINS {
+        if rng.gen() {
+            moves.push(pin.to_string());
+        }
+    }
+    moves.join(" ")
+}
+
+/// Writes turn of the dial by given amount, 0 is written as `0+`
+fn turn(pins: &str, amount: i32) -> String {
+    match amount {
+        a if a < 0 => format!("{pins}{}-", -a),
+        a => format!("{pins}{a}+"),
+    }
+}
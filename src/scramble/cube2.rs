@@ -0,0 +1,361 @@
+use std::sync::OnceLock;
+
+use rand::Rng;
+
+/// Moves used by the scramble, each face is turned clockwise, by half turn
+/// and counterclockwise. DBL corner isn't moved by any of them, so it's
+/// always solved
+const MOVES: [&str; 9] = ["U", "U2", "U'", "R", "R2", "R'", "F", "F2", "F'"];
+
+/// Number of permutations of the 7 corners which are moved
+const PERMS: usize = 5040;
+
+/// Number of orientations of the 7 corners which are moved (orientation of
+/// the last one is given by the others)
+const ORIS: usize = 729;
+
+/// Minimal number of moves solving the scrambled state, easier states are
+/// skipped as in the WCA scrambles
+const MIN_DISTANCE: usize = 4;
+
+/// Positions of the corners, which are moved (all but the DBL corner)
+const POSITIONS: [usize; 7] = [0, 1, 2, 3, 4, 5, 7];
+
+/// Corners of the cube in the order URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB.
+/// Permutation says which corner is on each position and orientation says
+/// how many times the corner is twisted clockwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Corners {
+    perm: [u8; 8],
+    ori: [u8; 8],
+}
+
+impl Corners {
+    /// Solved corners
+    const SOLVED: Corners = Corners {
+        perm: [0, 1, 2, 3, 4, 5, 6, 7],
+        ori: [0; 8],
+    };
+
+    /// Clockwise turns of the U, R and F faces
+    const FACES: [Corners; 3] = [
+        Corners {
+            perm: [3, 0, 1, 2, 4, 5, 6, 7],
+            ori: [0, 0, 0, 0, 0, 0, 0, 0],
+        },
+        Corners {
+            perm: [4, 1, 2, 0, 7, 5, 6, 3],
+            ori: [2, 0, 0, 1, 1, 0, 0, 2],
+        },
+        Corners {
+            perm: [1, 5, 2, 3, 0, 4, 6, 7],
+            ori: [1, 2, 0, 0, 2, 1, 0, 0],
+        },
+    ];
+
+    /// Applies given corners as a move to the corners
+    fn apply(&self, other: &Corners) -> Corners {
+        let mut res = Corners::SOLVED;
+        for i in 0..8 {
+            let from = other.perm[i] as usize;
+            res.perm[i] = self.perm[from];
+            res.ori[i] = (self.ori[from] + other.ori[i]) % 3;
+        }
+        res
+    }
+
+    /// Gets permutation coordinate of the moved corners (0 when solved)
+    fn perm_coord(&self) -> usize {
+        let perm = POSITIONS.map(|p| self.perm[p]);
+        let mut coord = 0;
+        for i in 0..perm.len() {
+            let smaller = perm[i + 1..].iter().filter(|c| **c < perm[i]);
+            coord = coord * (perm.len() - i) + smaller.count();
+        }
+        coord
+    }
+
+    /// Sets permutation of the moved corners based on the coordinate
+    fn set_perm_coord(&mut self, mut coord: usize) {
+        let mut digits = [0; 7];
+        for i in (0..digits.len()).rev() {
+            digits[i] = coord % (digits.len() - i);
+            coord /= digits.len() - i;
+        }
+
+        let mut left = POSITIONS.to_vec();
+        for (pos, digit) in POSITIONS.iter().zip(digits) {
+            self.perm[*pos] = left.remove(digit) as u8;
+        }
+    }
+
+    /// Gets orientation coordinate of the moved corners (0 when solved)
+    fn ori_coord(&self) -> usize {
+        POSITIONS[..6]
+            .iter()
+            .fold(0, |coord, p| coord * 3 + self.ori[*p] as usize)
+    }
+
+    /// Sets orientation of the moved corners based on the coordinate
+    fn set_ori_coord(&mut self, mut coord: usize) {
+        let mut sum = 0;
+        for pos in POSITIONS[..6].iter().rev() {
+            self.ori[*pos] = (coord % 3) as u8;
+            sum += coord % 3;
+            coord /= 3;
+        }
+        self.ori[7] = ((3 - sum % 3) % 3) as u8;
+    }
+}
+
+/// Move and pruning tables of the coordinates
+struct Tables {
+    perm_moves: Vec<[u16; 9]>,
+    ori_moves: Vec<[u16; 9]>,
+    /// Minimal number of moves solving the permutation
+    perm_dist: Vec<u8>,
+    /// Minimal number of moves solving the orientation
+    ori_dist: Vec<u8>,
+}
+
+impl Tables {
+    /// Gets the tables, they're generated on the first use
+    fn get() -> &'static Tables {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::generate)
+    }
+
+    /// Generates the tables
+    fn generate() -> Tables {
+        let moves = move_corners();
+        let perm_moves: Vec<[u16; 9]> = (0..PERMS)
+            .map(|coord| {
+                let mut corners = Corners::SOLVED;
+                corners.set_perm_coord(coord);
+                moves.map(|m| corners.apply(&m).perm_coord() as u16)
+            })
+            .collect();
+        let ori_moves: Vec<[u16; 9]> = (0..ORIS)
+            .map(|coord| {
+                let mut corners = Corners::SOLVED;
+                corners.set_ori_coord(coord);
+                moves.map(|m| corners.apply(&m).ori_coord() as u16)
+            })
+            .collect();
+
+        Tables {
+            perm_dist: distances(&perm_moves),
+            ori_dist: distances(&ori_moves),
+            perm_moves,
+            ori_moves,
+        }
+    }
+}
+
+/// Generates random-state 2x2x2 scramble. Uniformly random state is
+/// solved optimally and the inverse of the solution is the scramble
+pub fn generate(rng: &mut impl Rng) -> String {
+    let solution = loop {
+        let perm = rng.gen_range(0..PERMS);
+        let ori = rng.gen_range(0..ORIS);
+        let solution = solve(perm, ori);
+        if solution.len() >= MIN_DISTANCE {
+            break solution;
+        }
+    };
+
+    inverse(&solution)
+}
+
+/// Gets inverse of the solution, which is the scramble of the solved state
+fn inverse(solution: &[usize]) -> String {
+    solution
+        .iter()
+        .rev()
+        .map(|m| MOVES[m / 3 * 3 + 2 - m % 3])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds the shortest solution of the given state using IDA* search
+///
+/// **Returns:**
+/// * Indexes of the moves of the solution
+fn solve(perm: usize, ori: usize) -> Vec<usize> {
+    let tables = Tables::get();
+    let mut solution = Vec::new();
+    for depth in 0.. {
+        if search(tables, perm, ori, depth, &mut solution) {
+            break;
+        }
+    }
+    solution
+}
+
+/// Searches for the solution with given number of moves
+fn search(
+    tables: &Tables,
+    perm: usize,
+    ori: usize,
+    depth: usize,
+    solution: &mut Vec<usize>,
+) -> bool {
+    let dist = tables.perm_dist[perm].max(tables.ori_dist[ori]) as usize;
+    if dist > depth {
+        return false;
+    }
+    if depth == 0 {
+        return true;
+    }
+
+    let last = solution.last().map(|m| m / 3);
+    for m in 0..MOVES.len() {
+        // Turning the same face twice in a row is never optimal
+        if last == Some(m / 3) {
+            continue;
+        }
+
+        solution.push(m);
+        let next_perm = tables.perm_moves[perm][m] as usize;
+        let next_ori = tables.ori_moves[ori][m] as usize;
+        if search(tables, next_perm, next_ori, depth - 1, solution) {
+            return true;
+        }
+        solution.pop();
+    }
+    false
+}
+
+/// Gets corners of all the moves in the order of [`MOVES`]
+fn move_corners() -> [Corners; 9] {
+    let mut moves = [Corners::SOLVED; 9];
+    for (face, turn) in Corners::FACES.iter().enumerate() {
+        let mut corners = Corners::SOLVED;
+        for power in 0..3 {
+            corners = corners.apply(turn);
+            moves[face * 3 + power] = corners;
+        }
+    }
+    moves
+}
+
+/// Computes minimal number of moves solving each coordinate using breadth
+/// first search from the solved coordinate
+fn distances(moves: &[[u16; 9]]) -> Vec<u8> {
+    let mut dist = vec![u8::MAX; moves.len()];
+    dist[0] = 0;
+    let mut queue = vec![0];
+    let mut depth = 0;
+    while !queue.is_empty() {
+        let mut next = Vec::new();
+        for coord in queue {
+            for m in moves[coord] {
+                if dist[m as usize] == u8::MAX {
+                    dist[m as usize] = depth + 1;
+                    next.push(m as usize);
+                }
+            }
+        }
+        queue = next;
+        depth += 1;
+    }
+    dist
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{
+        generate, inverse, move_corners, solve, Corners, MIN_DISTANCE, MOVES,
+        ORIS, PERMS,
+    };
+
+    /// Gets corners with given coordinates
+    fn corners(perm: usize, ori: usize) -> Corners {
+        let mut corners = Corners::SOLVED;
+        corners.set_perm_coord(perm);
+        corners.set_ori_coord(ori);
+        corners
+    }
+
+    /// Applies moves with given indexes to the corners
+    fn apply(corners: Corners, moves: &[usize]) -> Corners {
+        let turns = move_corners();
+        moves.iter().fold(corners, |c, m| c.apply(&turns[*m]))
+    }
+
+    /// Gets indexes of the moves in the scramble
+    fn parse(scramble: &str) -> Vec<usize> {
+        scramble
+            .split(' ')
+            .map(|m| MOVES.iter().position(|n| *n == m).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn perm_coord() {
+        for coord in 0..PERMS {
+            let mut corners = Corners::SOLVED;
+            corners.set_perm_coord(coord);
+            assert_eq!(corners.perm_coord(), coord);
+        }
+    }
+
+    #[test]
+    fn ori_coord() {
+        for coord in 0..ORIS {
+            let mut corners = Corners::SOLVED;
+            corners.set_ori_coord(coord);
+            assert_eq!(corners.ori_coord(), coord);
+            assert_eq!(corners.ori.iter().sum::<u8>() % 3, 0);
+        }
+    }
+
+    #[test]
+    fn solution_solves() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut solved = 0;
+        while solved < 100 {
+            let (perm, ori) =
+                (rng.gen_range(0..PERMS), rng.gen_range(0..ORIS));
+            let solution = solve(perm, ori);
+            if solution.len() < MIN_DISTANCE {
+                continue;
+            }
+            let state = corners(perm, ori);
+            assert_eq!(apply(state, &solution), Corners::SOLVED);
+            solved += 1;
+        }
+    }
+
+    #[test]
+    fn scramble_is_inverse() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let (perm, ori) =
+                (rng.gen_range(0..PERMS), rng.gen_range(0..ORIS));
+            let solution = solve(perm, ori);
+            let scramble = inverse(&solution);
+            if solution.is_empty() {
+                assert!(scramble.is_empty());
+                continue;
+            }
+            let scrambled = apply(Corners::SOLVED, &parse(&scramble));
+            assert_eq!(scrambled, corners(perm, ori), "{scramble}");
+        }
+    }
+
+    #[test]
+    fn scramble_distance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let scramble = generate(&mut rng);
+            let scrambled = apply(Corners::SOLVED, &parse(&scramble));
+            let solution =
+                solve(scrambled.perm_coord(), scrambled.ori_coord());
+            assert!(solution.len() >= MIN_DISTANCE, "{scramble}");
+            assert_eq!(solution.len(), scramble.split(' ').count());
+        }
+    }
+}
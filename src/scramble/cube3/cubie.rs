@@ -0,0 +1,326 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// Number of corner orientations (orientation of the last corner is given
+/// by the others)
+pub const TWISTS: usize = 2187;
+
+/// Number of edge orientations (orientation of the last edge is given by
+/// the others)
+pub const FLIPS: usize = 2048;
+
+/// Number of positions and permutations of the UD-slice edges
+pub const SLICE_SORTED: usize = 11880;
+
+/// Number of permutations of the UD-slice edges
+pub const SLICE_PERMS: usize = 24;
+
+/// Number of corner permutations
+pub const CORNERS: usize = 40320;
+
+/// Number of permutations of the U and D edges, when all of them are in
+/// the U and D layers
+pub const UD_EDGES: usize = 40320;
+
+/// Number of the moves, each face (in order U, R, F, D, L, B) is turned
+/// clockwise, by half turn and counterclockwise
+pub const MOVES: usize = 18;
+
+/// Cube on the cubie level. Corners are in order URF, UFL, ULB, UBR, DFR,
+/// DLF, DBL, DRB and edges in order UR, UF, UL, UB, DR, DF, DL, DB, FR, FL,
+/// BL, BR. Permutation says which cubie is on each position and
+/// orientation says how is the cubie twisted or flipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CubieCube {
+    cp: [u8; 8],
+    co: [u8; 8],
+    ep: [u8; 12],
+    eo: [u8; 12],
+}
+
+impl CubieCube {
+    /// Solved cube
+    pub const SOLVED: CubieCube = CubieCube {
+        cp: [0, 1, 2, 3, 4, 5, 6, 7],
+        co: [0; 8],
+        ep: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        eo: [0; 12],
+    };
+
+    /// Clockwise turns of the faces in order U, R, F, D, L, B
+    const FACES: [CubieCube; 6] = [
+        CubieCube {
+            cp: [3, 0, 1, 2, 4, 5, 6, 7],
+            co: [0, 0, 0, 0, 0, 0, 0, 0],
+            ep: [3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
+            eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        },
+        CubieCube {
+            cp: [4, 1, 2, 0, 7, 5, 6, 3],
+            co: [2, 0, 0, 1, 1, 0, 0, 2],
+            ep: [8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
+            eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        },
+        CubieCube {
+            cp: [1, 5, 2, 3, 0, 4, 6, 7],
+            co: [1, 2, 0, 0, 2, 1, 0, 0],
+            ep: [0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
+            eo: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+        },
+        CubieCube {
+            cp: [0, 1, 2, 3, 5, 6, 7, 4],
+            co: [0, 0, 0, 0, 0, 0, 0, 0],
+            ep: [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
+            eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        },
+        CubieCube {
+            cp: [0, 2, 6, 3, 4, 1, 5, 7],
+            co: [0, 1, 2, 0, 0, 2, 1, 0],
+            ep: [0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
+            eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        },
+        CubieCube {
+            cp: [0, 1, 3, 7, 4, 5, 2, 6],
+            co: [0, 0, 1, 2, 0, 0, 2, 1],
+            ep: [0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
+            eo: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+        },
+    ];
+
+    /// Creates uniformly random cube, which can be solved
+    pub fn random(rng: &mut impl Rng) -> CubieCube {
+        let mut cube = CubieCube::SOLVED;
+        cube.cp.shuffle(rng);
+        cube.ep.shuffle(rng);
+        // Corner and edge permutations have to have the same parity
+        if parity(&cube.cp) != parity(&cube.ep) {
+            cube.ep.swap(0, 1);
+        }
+        cube.set_twist(rng.gen_range(0..TWISTS));
+        cube.set_flip(rng.gen_range(0..FLIPS));
+        cube
+    }
+
+    /// Gets cubes of all the moves, index of the move is `face * 3 + power`
+    pub fn moves() -> [CubieCube; MOVES] {
+        let mut moves = [CubieCube::SOLVED; MOVES];
+        for (face, turn) in CubieCube::FACES.iter().enumerate() {
+            let mut cube = CubieCube::SOLVED;
+            for power in 0..3 {
+                cube = cube.apply(turn);
+                moves[face * 3 + power] = cube;
+            }
+        }
+        moves
+    }
+
+    /// Applies given cube as a move to the cube
+    pub fn apply(&self, other: &CubieCube) -> CubieCube {
+        let mut res = CubieCube::SOLVED;
+        for i in 0..8 {
+            let from = other.cp[i] as usize;
+            res.cp[i] = self.cp[from];
+            res.co[i] = (self.co[from] + other.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.ep[i] as usize;
+            res.ep[i] = self.ep[from];
+            res.eo[i] = (self.eo[from] + other.eo[i]) % 2;
+        }
+        res
+    }
+
+    /// Gets corner orientation coordinate (0 when solved)
+    pub fn twist(&self) -> usize {
+        self.co[..7].iter().fold(0, |c, o| c * 3 + *o as usize)
+    }
+
+    /// Sets corner orientation based on the coordinate
+    pub fn set_twist(&mut self, mut twist: usize) {
+        let mut sum = 0;
+        for i in (0..7).rev() {
+            self.co[i] = (twist % 3) as u8;
+            sum += twist % 3;
+            twist /= 3;
+        }
+        self.co[7] = ((3 - sum % 3) % 3) as u8;
+    }
+
+    /// Gets edge orientation coordinate (0 when solved)
+    pub fn flip(&self) -> usize {
+        self.eo[..11].iter().fold(0, |c, o| c * 2 + *o as usize)
+    }
+
+    /// Sets edge orientation based on the coordinate
+    pub fn set_flip(&mut self, mut flip: usize) {
+        let mut sum = 0;
+        for i in (0..11).rev() {
+            self.eo[i] = (flip % 2) as u8;
+            sum += flip % 2;
+            flip /= 2;
+        }
+        self.eo[11] = (sum % 2) as u8;
+    }
+
+    /// Gets coordinate of positions and permutation of the UD-slice edges
+    /// (FR, FL, BL and BR). It's lower than [`SLICE_PERMS`] when the edges
+    /// are in the UD-slice and 0 when they're solved
+    pub fn slice_sorted(&self) -> usize {
+        let mut pos = 0;
+        let mut edges = Vec::with_capacity(4);
+        for j in (0..12).rev() {
+            if self.ep[j] >= 8 {
+                pos += binomial(11 - j, edges.len() + 1);
+                edges.insert(0, self.ep[j] - 8);
+            }
+        }
+        SLICE_PERMS * pos + perm_coord(&edges)
+    }
+
+    /// Sets edges based on the UD-slice coordinate, other edges are placed
+    /// in order to the remaining positions
+    pub fn set_slice_sorted(&mut self, coord: usize) {
+        let edges = set_perm_coord(coord % SLICE_PERMS, 4);
+        let mut pos = coord / SLICE_PERMS;
+
+        let mut left = 4;
+        let mut other = 0;
+        for j in 0..12 {
+            if left > 0 && pos >= binomial(11 - j, left) {
+                pos -= binomial(11 - j, left);
+                self.ep[j] = edges[4 - left] + 8;
+                left -= 1;
+            } else {
+                self.ep[j] = other;
+                other += 1;
+            }
+        }
+    }
+
+    /// Gets corner permutation coordinate (0 when solved)
+    pub fn corners(&self) -> usize {
+        perm_coord(&self.cp)
+    }
+
+    /// Sets corner permutation based on the coordinate
+    pub fn set_corners(&mut self, coord: usize) {
+        self.cp.copy_from_slice(&set_perm_coord(coord, 8));
+    }
+
+    /// Gets permutation coordinate of the U and D edges, valid only when
+    /// they're all in the U and D layers (0 when solved)
+    pub fn ud_edges(&self) -> usize {
+        perm_coord(&self.ep[..8])
+    }
+
+    /// Sets permutation of the U and D edges based on the coordinate, the
+    /// UD-slice edges are solved
+    pub fn set_ud_edges(&mut self, coord: usize) {
+        self.ep[..8].copy_from_slice(&set_perm_coord(coord, 8));
+        self.ep[8..].copy_from_slice(&[8, 9, 10, 11]);
+    }
+}
+
+/// Gets coordinate of the permutation of numbers from 0 to its length
+fn perm_coord(perm: &[u8]) -> usize {
+    let mut perm = perm.to_vec();
+    let mut coord = 0;
+    for j in (1..perm.len()).rev() {
+        let mut k = 0;
+        while perm[j] as usize != j {
+            perm[..=j].rotate_left(1);
+            k += 1;
+        }
+        coord = (j + 1) * coord + k;
+    }
+    coord
+}
+
+/// Gets permutation of the given length based on its coordinate
+fn set_perm_coord(mut coord: usize, len: usize) -> Vec<u8> {
+    let mut perm: Vec<u8> = (0..len as u8).collect();
+    for j in 0..len {
+        let k = coord % (j + 1);
+        coord /= j + 1;
+        perm[..=j].rotate_right(k);
+    }
+    perm
+}
+
+/// Gets parity of the permutation (whether the number of inversions is
+/// odd)
+fn parity(perm: &[u8]) -> bool {
+    let mut inversions = 0;
+    for i in 0..perm.len() {
+        inversions += perm[i + 1..].iter().filter(|p| **p < perm[i]).count();
+    }
+    inversions % 2 == 1
+}
+
+/// Gets binomial coefficient n choose k, which is 0 when k > n
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |res, i| res * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CubieCube, CORNERS, FLIPS, SLICE_PERMS, SLICE_SORTED, TWISTS, UD_EDGES,
+    };
+
+    /// Checks that setting each coordinate and getting it back gives the
+    /// same coordinate
+    fn round_trip(
+        size: usize,
+        set: fn(&mut CubieCube, usize),
+        get: fn(&CubieCube) -> usize,
+    ) {
+        for coord in 0..size {
+            let mut cube = CubieCube::SOLVED;
+            set(&mut cube, coord);
+            assert_eq!(get(&cube), coord);
+        }
+        assert_eq!(get(&CubieCube::SOLVED), 0);
+    }
+
+    #[test]
+    fn twist() {
+        round_trip(TWISTS, CubieCube::set_twist, CubieCube::twist);
+    }
+
+    #[test]
+    fn flip() {
+        round_trip(FLIPS, CubieCube::set_flip, CubieCube::flip);
+    }
+
+    #[test]
+    fn slice_sorted() {
+        round_trip(
+            SLICE_SORTED,
+            CubieCube::set_slice_sorted,
+            CubieCube::slice_sorted,
+        );
+    }
+
+    #[test]
+    fn slice_sorted_in_slice() {
+        for coord in 0..SLICE_SORTED {
+            let mut cube = CubieCube::SOLVED;
+            cube.set_slice_sorted(coord);
+            let in_slice = cube.ep[8..].iter().all(|e| *e >= 8);
+            assert_eq!(in_slice, coord < SLICE_PERMS);
+        }
+    }
+
+    #[test]
+    fn corners() {
+        round_trip(CORNERS, CubieCube::set_corners, CubieCube::corners);
+    }
+
+    #[test]
+    fn ud_edges() {
+        round_trip(UD_EDGES, CubieCube::set_ud_edges, CubieCube::ud_edges);
+    }
+}
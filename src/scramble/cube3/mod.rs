@@ -0,0 +1,291 @@
+use std::{
+    sync::{Condvar, Mutex, Once},
+    thread,
+};
+
+use rand::{thread_rng, Rng};
+
+use cubie::{CubieCube, MOVES};
+use tables::{Tables, PHASE2_MOVES};
+
+mod cubie;
+mod tables;
+
+/// Faces in the order of the moves
+const FACES: [&str; 6] = ["U", "R", "F", "D", "L", "B"];
+
+/// Suffixes of the clockwise, half turn and counterclockwise moves
+const POWERS: [&str; 3] = ["", "2", "'"];
+
+/// Maximal number of moves of the solution, there's always solution with
+/// at most 20 moves, but a bit longer one is found much faster
+const MAX_LENGTH: usize = 22;
+
+/// Number of scrambles kept ready by the background thread
+const POOL_SIZE: usize = 5;
+
+/// Scrambles generated on the background thread
+struct Pool {
+    scrambles: Mutex<Vec<String>>,
+    /// Notified when a scramble is taken from the pool
+    taken: Condvar,
+}
+
+static POOL: Pool = Pool {
+    scrambles: Mutex::new(Vec::new()),
+    taken: Condvar::new(),
+};
+
+/// Generates random-state 3x3x3 scramble. Uniformly random state is solved
+/// by the two-phase algorithm and the inverse of the solution is the
+/// scramble. Loads the solver tables when they aren't loaded yet, which can
+/// take a few seconds when they have to be generated
+pub fn generate(rng: &mut impl Rng) -> String {
+    let tables = Tables::get();
+    loop {
+        // Solution isn't found when the state needs more than MAX_LENGTH
+        // moves, another random state is tried then
+        let scramble = scramble(tables, &CubieCube::random(rng));
+        if !scramble.is_empty() {
+            return scramble;
+        }
+    }
+}
+
+/// Starts the background thread generating scrambles, when it isn't
+/// running yet
+fn start() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        // Callers fall back to other scrambles when the thread isn't running
+        _ = thread::Builder::new()
+            .name("scramble".to_owned())
+            .spawn(fill_pool);
+    });
+}
+
+/// Takes scramble generated on the background thread, the thread is
+/// started when it isn't running yet. The scramble is never generated on
+/// this thread, since loading the solver tables can take a few seconds
+///
+/// **Returns:**
+/// * Generated scramble, [`None`] when there's no scramble ready yet
+pub fn take() -> Option<String> {
+    start();
+
+    let scramble = POOL.scrambles.lock().ok()?.pop();
+    POOL.taken.notify_one();
+    scramble
+}
+
+/// Keeps [`POOL_SIZE`] scrambles in the pool, waits while it's full
+fn fill_pool() {
+    let mut rng = thread_rng();
+    loop {
+        let scramble = generate(&mut rng);
+        let Ok(mut scrambles) = POOL.scrambles.lock() else {
+            return;
+        };
+        scrambles.push(scramble);
+        while scrambles.len() >= POOL_SIZE {
+            scrambles = match POOL.taken.wait(scrambles) {
+                Ok(scrambles) => scrambles,
+                Err(_) => return,
+            };
+        }
+    }
+}
+
+/// Gets scramble of the cube, which is the inverse of its solution
+fn scramble(tables: &'static Tables, cube: &CubieCube) -> String {
+    solve(tables, cube)
+        .iter()
+        .rev()
+        .map(|m| format!("{}{}", FACES[m / 3], POWERS[2 - m % 3]))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds solution of the cube with at most [`MAX_LENGTH`] moves. Phase 1
+/// brings the cube to the group generated by U, D, R2, L2, F2 and B2 and
+/// phase 2 solves it using only these moves
+///
+/// **Returns:**
+/// * Indexes of the moves of the solution
+fn solve(tables: &'static Tables, cube: &CubieCube) -> Vec<usize> {
+    let mut search = Search {
+        tables,
+        cube: *cube,
+        moves: CubieCube::moves(),
+        solution: Vec::with_capacity(MAX_LENGTH),
+    };
+    let (twist, flip, slice) =
+        (cube.twist(), cube.flip(), cube.slice_sorted());
+    for depth in 0..=MAX_LENGTH {
+        if search.phase1(twist, flip, slice, depth) {
+            break;
+        }
+    }
+    search.solution
+}
+
+/// State of the two-phase search
+struct Search {
+    tables: &'static Tables,
+    /// Cube which is solved
+    cube: CubieCube,
+    moves: [CubieCube; MOVES],
+    solution: Vec<usize>,
+}
+
+impl Search {
+    /// Searches for the phase 1 solution with given number of moves and
+    /// continues with phase 2 after each one found
+    fn phase1(
+        &mut self,
+        twist: usize,
+        flip: usize,
+        slice: usize,
+        depth: usize,
+    ) -> bool {
+        let dist = self.tables.phase1_dist(twist, flip, slice) as usize;
+        if dist > depth {
+            return false;
+        }
+        if depth == 0 {
+            // Phase 1 ending with phase 2 move was already tried shorter
+            let last = self.solution.last();
+            if last.is_some_and(|m| PHASE2_MOVES.contains(m)) {
+                return false;
+            }
+            return self.start_phase2();
+        }
+
+        for m in 0..MOVES {
+            if !self.can_follow(m) {
+                continue;
+            }
+            self.solution.push(m);
+            let t = self.tables.twist_moves[twist][m] as usize;
+            let f = self.tables.flip_moves[flip][m] as usize;
+            let s = self.tables.slice_moves[slice][m] as usize;
+            if self.phase1(t, f, s, depth - 1) {
+                return true;
+            }
+            self.solution.pop();
+        }
+        false
+    }
+
+    /// Starts phase 2 from the cube after the phase 1 moves, phase 2 can
+    /// use the moves left to [`MAX_LENGTH`]
+    fn start_phase2(&mut self) -> bool {
+        let cube = self
+            .solution
+            .iter()
+            .fold(self.cube, |cube, m| cube.apply(&self.moves[*m]));
+        let (corners, edges) = (cube.corners(), cube.ud_edges());
+        let slice = cube.slice_sorted();
+
+        let max = MAX_LENGTH - self.solution.len();
+        let dist = self.tables.phase2_dist(corners, edges, slice) as usize;
+        (dist..=max).any(|depth| self.phase2(corners, edges, slice, depth))
+    }
+
+    /// Searches for the phase 2 solution with given number of moves
+    fn phase2(
+        &mut self,
+        corners: usize,
+        edges: usize,
+        slice: usize,
+        depth: usize,
+    ) -> bool {
+        let dist = self.tables.phase2_dist(corners, edges, slice) as usize;
+        if dist > depth {
+            return false;
+        }
+        if depth == 0 {
+            return true;
+        }
+
+        for m in PHASE2_MOVES {
+            if !self.can_follow(m) {
+                continue;
+            }
+            self.solution.push(m);
+            let c = self.tables.corner_moves[corners][m] as usize;
+            let e = self.tables.ud_edge_moves[edges][m] as usize;
+            let s = self.tables.slice_moves[slice][m] as usize;
+            if self.phase2(c, e, s, depth - 1) {
+                return true;
+            }
+            self.solution.pop();
+        }
+        false
+    }
+
+    /// Checks whether the move can follow the last move. The same face is
+    /// never turned twice in a row and opposite faces are turned only in
+    /// one order, since they commute
+    fn can_follow(&self, m: usize) -> bool {
+        let Some(last) = self.solution.last().map(|l| l / 3) else {
+            return true;
+        };
+        let face = m / 3;
+        face != last && (face % 3 != last % 3 || face > last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        cubie::CubieCube, scramble, solve, tables::tests::generated, FACES,
+        MAX_LENGTH, POWERS,
+    };
+
+    /// Number of the solved cubes
+    const COUNT: usize = 20;
+
+    /// Applies moves with given indexes to the cube
+    fn apply(cube: CubieCube, moves: &[usize]) -> CubieCube {
+        let turns = CubieCube::moves();
+        moves.iter().fold(cube, |c, m| c.apply(&turns[*m]))
+    }
+
+    /// Gets indexes of the moves in the scramble
+    fn parse(scramble: &str) -> Vec<usize> {
+        scramble
+            .split(' ')
+            .map(|m| {
+                let (face, power) = m.split_at(1);
+                let face = FACES.iter().position(|f| *f == face).unwrap();
+                let power = POWERS.iter().position(|p| *p == power).unwrap();
+                face * 3 + power
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solution_solves() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..COUNT {
+            let cube = CubieCube::random(&mut rng);
+            let solution = solve(generated(), &cube);
+            assert!(solution.len() <= MAX_LENGTH);
+            assert_eq!(apply(cube, &solution), CubieCube::SOLVED);
+        }
+    }
+
+    #[test]
+    fn scramble_is_inverse() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..COUNT {
+            let cube = CubieCube::random(&mut rng);
+            let scramble = scramble(generated(), &cube);
+            let scrambled = apply(CubieCube::SOLVED, &parse(&scramble));
+            assert_eq!(scrambled, cube, "{scramble}");
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    fs::{create_dir_all, read},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    error::Error,
+    paths,
+    scramble::cube3::cubie::{
+        CubieCube, CORNERS, FLIPS, MOVES, SLICE_PERMS, SLICE_SORTED, TWISTS,
+        UD_EDGES,
+    },
+    storage::write_atomic,
+};
+
+/// Name of the file the tables are cached in
+const FILE_NAME: &str = "scramble-tables.bin";
+
+/// Header of the cache file, number at the end is the version of the tables
+/// format
+const HEADER: &[u8; 8] = b"RSTMTBL2";
+
+/// Number of positions of the UD-slice edges
+const SLICES: usize = SLICE_SORTED / SLICE_PERMS;
+
+/// Moves which keep the cube in the phase 2 group (U, D, R2, L2, F2, B2)
+pub const PHASE2_MOVES: [usize; 10] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16];
+
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+/// Move tables and pruning tables of the two-phase solver. Move tables
+/// give the coordinate after each move and pruning tables give the lower
+/// bound of the moves needed to solve the coordinates
+#[derive(Debug, PartialEq)]
+pub struct Tables {
+    pub twist_moves: Vec<[u16; MOVES]>,
+    pub flip_moves: Vec<[u16; MOVES]>,
+    pub slice_moves: Vec<[u16; MOVES]>,
+    pub corner_moves: Vec<[u16; MOVES]>,
+    /// Only moves of phase 2 are valid
+    pub ud_edge_moves: Vec<[u16; MOVES]>,
+    /// Phase 1 distance by the slice position and twist
+    pub slice_twist_dist: Vec<u8>,
+    /// Phase 1 distance by the slice position and flip
+    pub slice_flip_dist: Vec<u8>,
+    /// Phase 2 distance by the corners and slice permutation
+    pub corner_slice_dist: Vec<u8>,
+    /// Phase 2 distance by the U and D edges and slice permutation
+    pub edge_slice_dist: Vec<u8>,
+}
+
+impl Tables {
+    /// Gets the tables. They're loaded from the cache file on the first
+    /// use, or generated and saved to it when it's missing or invalid
+    pub fn get() -> &'static Tables {
+        TABLES.get_or_init(|| {
+            let path = Tables::path().ok();
+            if let Some(tables) = path.as_deref().and_then(Tables::load) {
+                return tables;
+            }
+            let tables = Tables::generate();
+            // Tables are generated again next time when saving fails
+            if let Some(path) = path {
+                _ = tables.save(&path);
+            }
+            tables
+        })
+    }
+
+    /// Gets lower bound of the phase 1 moves
+    pub fn phase1_dist(&self, twist: usize, flip: usize, slice: usize) -> u8 {
+        let slice = slice / SLICE_PERMS;
+        self.slice_twist_dist[slice * TWISTS + twist]
+            .max(self.slice_flip_dist[slice * FLIPS + flip])
+    }
+
+    /// Gets lower bound of the phase 2 moves
+    pub fn phase2_dist(
+        &self,
+        corners: usize,
+        edges: usize,
+        slice: usize,
+    ) -> u8 {
+        self.corner_slice_dist[corners * SLICE_PERMS + slice]
+            .max(self.edge_slice_dist[edges * SLICE_PERMS + slice])
+    }
+
+    /// Generates all the tables
+    fn generate() -> Tables {
+        let all: Vec<usize> = (0..MOVES).collect();
+        let twist_moves =
+            move_table(TWISTS, &all, CubieCube::set_twist, CubieCube::twist);
+        let flip_moves =
+            move_table(FLIPS, &all, CubieCube::set_flip, CubieCube::flip);
+        let slice_moves = move_table(
+            SLICE_SORTED,
+            &all,
+            CubieCube::set_slice_sorted,
+            CubieCube::slice_sorted,
+        );
+        let corner_moves = move_table(
+            CORNERS,
+            &all,
+            CubieCube::set_corners,
+            CubieCube::corners,
+        );
+        let ud_edge_moves = move_table(
+            UD_EDGES,
+            &PHASE2_MOVES,
+            CubieCube::set_ud_edges,
+            CubieCube::ud_edges,
+        );
+
+        let slice = |s: usize, m: usize| {
+            slice_moves[s * SLICE_PERMS][m] as usize / SLICE_PERMS
+        };
+        let slice_twist_dist = distances(SLICES * TWISTS, &all, |i, m| {
+            let (s, t) = (i / TWISTS, i % TWISTS);
+            slice(s, m) * TWISTS + twist_moves[t][m] as usize
+        });
+        let slice_flip_dist = distances(SLICES * FLIPS, &all, |i, m| {
+            let (s, f) = (i / FLIPS, i % FLIPS);
+            slice(s, m) * FLIPS + flip_moves[f][m] as usize
+        });
+        let corner_slice_dist =
+            distances(CORNERS * SLICE_PERMS, &PHASE2_MOVES, |i, m| {
+                let (c, s) = (i / SLICE_PERMS, i % SLICE_PERMS);
+                corner_moves[c][m] as usize * SLICE_PERMS
+                    + slice_moves[s][m] as usize
+            });
+        let edge_slice_dist =
+            distances(UD_EDGES * SLICE_PERMS, &PHASE2_MOVES, |i, m| {
+                let (e, s) = (i / SLICE_PERMS, i % SLICE_PERMS);
+                ud_edge_moves[e][m] as usize * SLICE_PERMS
+                    + slice_moves[s][m] as usize
+            });
+
+        Tables {
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_moves,
+            ud_edge_moves,
+            slice_twist_dist,
+            slice_flip_dist,
+            corner_slice_dist,
+            edge_slice_dist,
+        }
+    }
+
+    /// Loads the tables from the cache file. The file ends with checksum
+    /// of the tables, so corrupted file is detected
+    ///
+    /// **Parameters:**
+    /// * `path` - path to the cache file
+    ///
+    /// **Returns:**
+    /// * Loaded tables, [`None`] when the file is missing or invalid
+    fn load(path: &Path) -> Option<Tables> {
+        let data = read(path).ok()?;
+        let (data, sum) = data.strip_prefix(HEADER)?.split_last_chunk()?;
+        if checksum(data) != u64::from_le_bytes(*sum) {
+            return None;
+        }
+
+        let mut data = data;
+        let mut moves = |len: usize| -> Option<Vec<_>> {
+            let (table, rest) = data.split_at_checked(len * MOVES * 2)?;
+            data = rest;
+            table
+                .chunks_exact(MOVES * 2)
+                .map(|c| {
+                    let mut res = [0; MOVES];
+                    for (r, b) in res.iter_mut().zip(c.chunks_exact(2)) {
+                        *r = u16::from_le_bytes([b[0], b[1]]);
+                    }
+                    // Coordinate out of range would index out of the tables
+                    res.iter().all(|r| (*r as usize) < len).then_some(res)
+                })
+                .collect()
+        };
+
+        let twist_moves = moves(TWISTS)?;
+        let flip_moves = moves(FLIPS)?;
+        let slice_moves = moves(SLICE_SORTED)?;
+        let corner_moves = moves(CORNERS)?;
+        let ud_edge_moves = moves(UD_EDGES)?;
+
+        let mut dist = |len: usize| {
+            let (table, rest) = data.split_at_checked(len)?;
+            data = rest;
+            Some(table.to_vec())
+        };
+        let tables = Tables {
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_moves,
+            ud_edge_moves,
+            slice_twist_dist: dist(SLICES * TWISTS)?,
+            slice_flip_dist: dist(SLICES * FLIPS)?,
+            corner_slice_dist: dist(CORNERS * SLICE_PERMS)?,
+            edge_slice_dist: dist(UD_EDGES * SLICE_PERMS)?,
+        };
+        data.is_empty().then_some(tables)
+    }
+
+    /// Saves the tables to the cache file on given path
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut data = HEADER.to_vec();
+        for table in [
+            &self.twist_moves,
+            &self.flip_moves,
+            &self.slice_moves,
+            &self.corner_moves,
+            &self.ud_edge_moves,
+        ] {
+            for coord in table {
+                data.extend(coord.iter().flat_map(|c| c.to_le_bytes()));
+            }
+        }
+        for table in [
+            &self.slice_twist_dist,
+            &self.slice_flip_dist,
+            &self.corner_slice_dist,
+            &self.edge_slice_dist,
+        ] {
+            data.extend(table);
+        }
+        let sum = checksum(&data[HEADER.len()..]);
+        data.extend(sum.to_le_bytes());
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        write_atomic(path, &data)
+    }
+
+    /// Gets path to the cache file
+    fn path() -> Result<PathBuf, Error> {
+        Ok(paths::data_dir()?.join(FILE_NAME))
+    }
+}
+
+/// Computes FNV-1a hash of the data, used as the checksum of the cache file
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Creates move table of the coordinate
+///
+/// **Parameters:**
+/// * `size` - number of the coordinates
+/// * `moves` - moves which are computed, others are left 0
+/// * `set` - sets the coordinate of the cube
+/// * `get` - gets the coordinate of the cube
+fn move_table(
+    size: usize,
+    moves: &[usize],
+    set: fn(&mut CubieCube, usize),
+    get: fn(&CubieCube) -> usize,
+) -> Vec<[u16; MOVES]> {
+    let cubes = CubieCube::moves();
+    (0..size)
+        .map(|coord| {
+            let mut cube = CubieCube::SOLVED;
+            set(&mut cube, coord);
+            let mut res = [0; MOVES];
+            for m in moves {
+                res[*m] = get(&cube.apply(&cubes[*m])) as u16;
+            }
+            res
+        })
+        .collect()
+}
+
+/// Computes minimal number of moves solving each coordinate using breadth
+/// first search from the solved coordinate
+///
+/// **Parameters:**
+/// * `size` - number of the coordinates
+/// * `moves` - moves which can be used
+/// * `apply` - gets the coordinate after the move
+fn distances(
+    size: usize,
+    moves: &[usize],
+    apply: impl Fn(usize, usize) -> usize,
+) -> Vec<u8> {
+    let mut dist = vec![u8::MAX; size];
+    dist[0] = 0;
+    let mut queue = vec![0];
+    let mut depth = 0;
+    while !queue.is_empty() {
+        let mut next = Vec::new();
+        for coord in queue {
+            for m in moves {
+                let moved = apply(coord, *m);
+                if dist[moved] == u8::MAX {
+                    dist[moved] = depth + 1;
+                    next.push(moved);
+                }
+            }
+        }
+        queue = next;
+        depth += 1;
+    }
+    dist
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use std::{
+        fs::{read, remove_file, write},
+        path::PathBuf,
+        sync::OnceLock,
+    };
+
+    use super::{checksum, Tables, HEADER};
+
+    /// Gets generated tables, they're generated only once for all tests
+    pub fn generated() -> &'static Tables {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::generate)
+    }
+
+    /// Gets path to the cache file used by the test
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("rstimer-{}-{name}.bin", std::process::id()))
+    }
+
+    #[test]
+    fn save_load() {
+        let path = temp_path("tables");
+        generated().save(&path).unwrap();
+        let loaded = Tables::load(&path);
+        _ = remove_file(&path);
+        assert!(loaded.is_some_and(|t| t == *generated()));
+    }
+
+    #[test]
+    fn load_corrupted() {
+        let path = temp_path("corrupted");
+        generated().save(&path).unwrap();
+        let data = read(&path).unwrap();
+        let load = |data: &[u8]| {
+            write(&path, data).unwrap();
+            Tables::load(&path)
+        };
+
+        // Move table entry out of the coordinate range
+        let mut out_of_range = data.clone();
+        out_of_range[HEADER.len()..HEADER.len() + 2]
+            .copy_from_slice(&u16::MAX.to_le_bytes());
+        let corrupted = load(&out_of_range);
+
+        // Same entry with matching checksum
+        let end = out_of_range.len() - 8;
+        let sum = checksum(&out_of_range[HEADER.len()..end]);
+        out_of_range[end..].copy_from_slice(&sum.to_le_bytes());
+        let out_of_range = load(&out_of_range);
+
+        let truncated = load(&data[..data.len() - 1]);
+        _ = remove_file(&path);
+        assert!(corrupted.is_none());
+        assert!(out_of_range.is_none());
+        assert!(truncated.is_none());
+    }
+}
@@ -0,0 +1,51 @@
+use rand::Rng;
+
+/// Number of lines of the scramble
+const LINES: usize = 7;
+
+/// Number of R and D moves on each line
+const LINE_LENGTH: usize = 10;
+
+/// Generates Pochmann style Megaminx scramble. Each line alternates R and
+/// D moves turned by two fifths (`++` or `--`) and ends with U after D++
+/// or with U' after D--
+pub fn generate(rng: &mut impl Rng) -> String {
+    let mut lines = Vec::with_capacity(LINES);
+    for _ in 0..LINES {
+        let mut line = Vec::with_capacity(LINE_LENGTH + 1);
+        let mut clockwise = true;
+        for i in 0..LINE_LENGTH {
+            let face = if i % 2 == 0 { 'R' } else { 'D' };
+            clockwise = rng.gen();
+            let dir = if clockwise { "++" } else { "--" };
+            line.push(format!("{face}{dir}"));
+        }
+        // Line ends with D move, U is turned the same direction
+        line.push(if clockwise { "U" } else { "U'" }.to_string());
+        lines.push(line.join(" "));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::generate;
+
+    #[test]
+    fn u_follows_last_d() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            for line in generate(&mut rng).lines() {
+                let moves: Vec<_> = line.split(' ').collect();
+                let expected = match moves[moves.len() - 2] {
+                    "D++" => "U",
+                    "D--" => "U'",
+                    d => panic!("expected D move, got '{d}'"),
+                };
+                assert_eq!(moves[moves.len() - 1], expected, "{line}");
+            }
+        }
+    }
+}
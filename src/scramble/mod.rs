@@ -0,0 +1,426 @@
+use std::str::FromStr;
+
+use rand::{thread_rng, Rng};
+
+use crate::error::Error;
+
+mod clock;
+mod cube2;
+mod cube3;
+mod megaminx;
+mod square1;
+
+/// Scramble struct containing kind of the scramble and current scramble
+#[derive(Debug)]
+pub struct Scramble {
+    kind: Kind,
+    scramble: String,
+}
+
+/// Kind of the scramble, which decides how the scramble is generated
+#[derive(Debug)]
+enum Kind {
+    /// Random moves from the move groups, consecutive moves are from
+    /// different groups
+    Moves {
+        length: usize,
+        moves: Vec<MoveGroup>,
+    },
+    /// Random state of the 2x2x2 solved by the built-in solver
+    Cube2,
+    /// Random state of the 3x3x3 solved by the two-phase solver
+    Cube3,
+    /// Random moves followed by random tip moves
+    Pyraminx,
+    /// Pochmann style lines of R and D moves ending with U move
+    Megaminx,
+    /// Dial turns with pin states at the end
+    Clock,
+    /// Layer turns separated by slashes
+    Square1,
+}
+
+impl Scramble {
+    /// All supported scramble types
+    pub const TYPES: [&'static str; 11] = [
+        "2x2x2", "3x3x3", "4x4x4", "5x5x5", "6x6x6", "7x7x7", "Pyraminx",
+        "Skewb", "Megaminx", "Clock", "Square-1",
+    ];
+
+    /// Creates new [`Scramble`] based on the given type.
+    /// Returns scramble with zero length and no valid moves
+    pub fn new(scramble_type: &str) -> Scramble {
+        match scramble_type {
+            "2x2x2" => Scramble::kind(Kind::Cube2),
+            "3x3x3" => Scramble::kind(Kind::Cube3),
+            "4x4x4" => Scramble::custom(43, Scramble::get_4x4x4()),
+            "5x5x5" => Scramble::custom(60, Scramble::get_5x5x5()),
+            "6x6x6" => Scramble::custom(80, Scramble::get_6x6x6()),
+            "7x7x7" => Scramble::custom(100, Scramble::get_7x7x7()),
+            "Pyraminx" => Scramble::kind(Kind::Pyraminx),
+            "Skewb" => Scramble::custom(11, Scramble::get_skewb()),
+            "Megaminx" => Scramble::kind(Kind::Megaminx),
+            "Clock" => Scramble::kind(Kind::Clock),
+            "Square-1" => Scramble::kind(Kind::Square1),
+            _ => Scramble::custom(0, vec![]),
+        }
+    }
+
+    /// Creates new [`Scramble`] based on the given type, the type is
+    /// matched case insensitively
+    ///
+    /// **Returns:**
+    /// * Created [`Scramble`], error when the type isn't supported
+    pub fn try_new(scramble_type: &str) -> Result<Scramble, Error> {
+        Scramble::TYPES
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(scramble_type))
+            .map(|t| Scramble::new(t))
+            .ok_or(Error::Msg(format!(
+                "unknown scramble type '{scramble_type}', supported types \
+                are: {}",
+                Scramble::TYPES.join(", ")
+            )))
+    }
+
+    /// Creates new custom [`Scramble`] with given length and move groups
+    pub fn custom(len: usize, moves: Vec<MoveGroup>) -> Scramble {
+        Scramble::kind(Kind::Moves { length: len, moves })
+    }
+
+    /// Generates new scramble. 3x3x3 scrambles are taken from the ones
+    /// generated in the background, which starts on the first call. Random
+    /// moves are used until the first scramble is ready
+    pub fn generate(&mut self) {
+        if let Kind::Cube3 = self.kind {
+            self.scramble = cube3::take().unwrap_or_else(|| {
+                random_moves(&mut thread_rng(), 21, &Scramble::get_3x3x3())
+                    .join(" ")
+            });
+            return;
+        }
+        self.generate_with(&mut thread_rng());
+    }
+
+    /// Generates new scramble using given random number generator, so
+    /// seeded generator gives the same scrambles
+    pub fn generate_with(&mut self, rng: &mut impl Rng) {
+        self.scramble = match &self.kind {
+            Kind::Moves { length, moves } => {
+                random_moves(rng, *length, moves).join(" ")
+            }
+            Kind::Cube2 => cube2::generate(rng),
+            Kind::Cube3 => cube3::generate(rng),
+            Kind::Pyraminx => {
+                let mut moves =
+                    random_moves(rng, 11, &Scramble::get_pyraminx());
+                // Each tip is turned either way or not at all
+                for tip in PYRAMINX_TIPS {
+                    if let Some(turn) = tip.get(rng.gen_range(0..3)) {
+                        moves.push(turn);
+                    }
+                }
+                moves.join(" ")
+            }
+            Kind::Megaminx => megaminx::generate(rng),
+            Kind::Clock => clock::generate(rng),
+            Kind::Square1 => square1::generate(rng),
+        };
+    }
+
+    /// Gets scramble
+    pub fn get(&self) -> &str {
+        &self.scramble
+    }
+
+    /// Creates new [`Scramble`] of the given kind
+    fn kind(kind: Kind) -> Scramble {
+        Scramble {
+            kind,
+            scramble: "".to_owned(),
+        }
+    }
+}
+
+/// Group of moves turning the same face together with the axis of the
+/// face. Moves on the same axis commute, so they can cancel out even when
+/// they aren't next to each other
+pub type MoveGroup = (usize, Vec<&'static str>);
+
+/// Tip moves of the Pyraminx
+const PYRAMINX_TIPS: [[&str; 2]; 4] =
+    [["u", "u'"], ["l", "l'"], ["r", "r'"], ["b", "b'"]];
+
+/// Picks given number of random moves. Consecutive moves are from
+/// different move groups and there are never three consecutive moves on
+/// the same axis, so no moves cancel out (like `R L R'`)
+fn random_moves(
+    rng: &mut impl Rng,
+    length: usize,
+    moves: &[MoveGroup],
+) -> Vec<&'static str> {
+    let mut scramble = Vec::with_capacity(length);
+    // Move groups of the last two moves
+    let mut last: Option<usize> = None;
+    let mut second: Option<usize> = None;
+
+    let axis = |group: Option<usize>| group.map(|g| moves[g].0);
+    for _ in 0..length {
+        let r = loop {
+            let r = rng.gen_range(0..moves.len());
+            let same_axis = axis(last) == Some(moves[r].0)
+                && axis(second) == Some(moves[r].0);
+            if last != Some(r) && !same_axis {
+                break r;
+            }
+        };
+        (second, last) = (last, Some(r));
+
+        let group = &moves[r].1;
+        scramble.push(group[rng.gen_range(0..group.len())]);
+    }
+    scramble
+}
+
+/// Output format of the scramble command
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Output {
+    /// Scramble on each line
+    #[default]
+    Plain,
+    /// Numbered list of the scrambles
+    Numbered,
+    /// JSON object with scramble type and list of the scrambles
+    Json,
+}
+
+impl FromStr for Output {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(Output::Plain),
+            "numbered" => Ok(Output::Numbered),
+            "json" => Ok(Output::Json),
+            _ => Err(Error::Msg(format!("unknown output format '{s}'"))),
+        }
+    }
+}
+
+impl Scramble {
+    /// Gets move groups for 3x3x3 scramble
+    fn get_3x3x3() -> Vec<MoveGroup> {
+        vec![
+            (0, vec!["R", "R'", "R2"]),
+            (0, vec!["L", "L'", "L2"]),
+            (1, vec!["U", "U'", "U2"]),
+            (1, vec!["D", "D'", "D2"]),
+            (2, vec!["F", "F'", "F2"]),
+            (2, vec!["B", "B'", "B2"]),
+        ]
+    }
+
+    /// Gets move groups for 4x4x4 scramble
+    fn get_4x4x4() -> Vec<MoveGroup> {
+        vec![
+            (0, vec!["R", "R'", "R2", "Rw", "Rw'", "Rw2"]),
+            (0, vec!["L", "L'", "L2"]),
+            (1, vec!["U", "U'", "U2", "Uw", "Uw'", "Uw2"]),
+            (1, vec!["D", "D'", "D2"]),
+            (2, vec!["F", "F'", "F2", "Fw", "Fw'", "Fw2"]),
+            (2, vec!["B", "B'", "B2"]),
+        ]
+    }
+
+    /// Gets move groups for 5x5x5 scramble
+    fn get_5x5x5() -> Vec<MoveGroup> {
+        vec![
+            (0, vec!["R", "R'", "R2", "Rw", "Rw'", "Rw2"]),
+            (0, vec!["L", "L'", "L2", "Lw", "Lw'", "Lw2"]),
+            (1, vec!["U", "U'", "U2", "Uw", "Uw'", "Uw2"]),
+            (1, vec!["D", "D'", "D2", "Dw", "Dw'", "Dw2"]),
+            (2, vec!["F", "F'", "F2", "Fw", "Fw'", "Fw2"]),
+            (2, vec!["B", "B'", "B2", "Bw", "Bw'", "Bw2"]),
+        ]
+    }
+
+    /// Gets move groups for 6x6x6 scramble
+    fn get_6x6x6() -> Vec<MoveGroup> {
+        vec![
+            (
+                0,
+                vec![
+                    "R", "R'", "R2", "Rw", "Rw'", "Rw2", "3Rw", "3Rw'", "3Rw2",
+                ],
+            ),
+            (0, vec!["L", "L'", "L2", "Lw", "Lw'", "Lw2"]),
+            (
+                1,
+                vec![
+                    "U", "U'", "U2", "Uw", "Uw'", "Uw2", "3Uw", "3Uw'", "3Uw2",
+                ],
+            ),
+            (1, vec!["D", "D'", "D2", "Dw", "Dw'", "Dw2"]),
+            (
+                2,
+                vec![
+                    "F", "F'", "F2", "Fw", "Fw'", "Fw2", "3Fw", "3Fw'", "3Fw2",
+                ],
+            ),
+            (2, vec!["B", "B'", "B2", "Bw", "Bw'", "Bw2"]),
+        ]
+    }
+
+    /// Gets move groups for 7x7x7 scramble
+    fn get_7x7x7() -> Vec<MoveGroup> {
+        vec![
+            (
+                0,
+                vec![
+                    "R", "R'", "R2", "Rw", "Rw'", "Rw2", "3Rw", "3Rw'", "3Rw2",
+                ],
+            ),
+            (
+                0,
+                vec![
+                    "L", "L'", "L2", "Lw", "Lw'", "Lw2", "3Lw", "3Lw'", "3Lw2",
+                ],
+            ),
+            (
+                1,
+                vec![
+                    "U", "U'", "U2", "Uw", "Uw'", "Uw2", "3Uw", "3Uw'", "3Uw2",
+                ],
+            ),
+            (
+                1,
+                vec![
+                    "D", "D'", "D2", "Dw", "Dw'", "Dw2", "3Dw", "3Dw'", "3Dw2",
+                ],
+            ),
+            (
+                2,
+                vec![
+                    "F", "F'", "F2", "Fw", "Fw'", "Fw2", "3Fw", "3Fw'", "3Fw2",
+                ],
+            ),
+            (
+                2,
+                vec![
+                    "B", "B'", "B2", "Bw", "Bw'", "Bw2", "3Bw", "3Bw'", "3Bw2",
+                ],
+            ),
+        ]
+    }
+
+    /// Gets move groups for Pyraminx scramble
+    fn get_pyraminx() -> Vec<MoveGroup> {
+        vec![
+            (0, vec!["U", "U'"]),
+            (1, vec!["L", "L'"]),
+            (2, vec!["R", "R'"]),
+            (3, vec!["B", "B'"]),
+        ]
+    }
+
+    /// Gets move groups for Skewb scramble
+    fn get_skewb() -> Vec<MoveGroup> {
+        vec![
+            (0, vec!["R", "R'"]),
+            (1, vec!["U", "U'"]),
+            (2, vec!["L", "L'"]),
+            (3, vec!["B", "B'"]),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{MoveGroup, Scramble};
+
+    /// Number of scrambles generated of each type
+    const COUNT: usize = 1000;
+
+    /// Reduces the scramble by merging each move into an earlier move on
+    /// the same face, which is possible when all moves between them are on
+    /// the same axis (like `R L R'`). Moves not in the move groups (like
+    /// Pyraminx tips) are skipped
+    ///
+    /// **Returns:**
+    /// * Number of the moves left after the reduction
+    fn reduce(scramble: &str, moves: &[MoveGroup]) -> usize {
+        let mut reduced: Vec<usize> = Vec::new();
+        let groups = scramble
+            .split(' ')
+            .filter_map(|m| moves.iter().position(|(_, g)| g.contains(&m)));
+        for group in groups {
+            let axis = moves[group].0;
+            let merged = reduced
+                .iter()
+                .rev()
+                .take_while(|g| moves[**g].0 == axis)
+                .any(|g| *g == group);
+            if !merged {
+                reduced.push(group);
+            }
+        }
+        reduced.len()
+    }
+
+    /// Checks that the scramble can't be shortened by merging moves
+    fn check(scramble: &str, moves: &[MoveGroup]) {
+        let len = scramble
+            .split(' ')
+            .filter(|m| moves.iter().any(|(_, g)| g.contains(m)))
+            .count();
+        assert_eq!(
+            reduce(scramble, moves),
+            len,
+            "moves cancel in '{scramble}'"
+        );
+    }
+
+    #[test]
+    fn reduce_merges_moves() {
+        let moves = Scramble::get_3x3x3();
+        assert_eq!(reduce("R U R' F", &moves), 4);
+        assert_eq!(reduce("R R'", &moves), 1);
+        assert_eq!(reduce("R L R'", &moves), 2);
+        assert_eq!(reduce("R L U R", &moves), 4);
+    }
+
+    #[test]
+    fn moves_dont_cancel() {
+        let types = [
+            (21, Scramble::get_3x3x3()),
+            (43, Scramble::get_4x4x4()),
+            (60, Scramble::get_5x5x5()),
+            (80, Scramble::get_6x6x6()),
+            (100, Scramble::get_7x7x7()),
+            (11, Scramble::get_skewb()),
+            (11, Scramble::get_pyraminx()),
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        for (length, moves) in types {
+            let mut scramble = Scramble::custom(length, moves.clone());
+            for _ in 0..COUNT {
+                scramble.generate_with(&mut rng);
+                assert_eq!(scramble.get().split(' ').count(), length);
+                check(scramble.get(), &moves);
+            }
+        }
+    }
+
+    #[test]
+    fn pyraminx_moves_dont_cancel() {
+        let moves = Scramble::get_pyraminx();
+        let mut scramble = Scramble::new("Pyraminx");
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..COUNT {
+            scramble.generate_with(&mut rng);
+            check(scramble.get(), &moves);
+        }
+    }
+}
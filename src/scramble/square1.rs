@@ -0,0 +1,99 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// Number of slashes in the scramble
+const SLASHES: usize = 12;
+
+/// Layer split to 12 slots of 30 degrees, each slot contains id of the
+/// piece in it, so corners take two slots. Slots are indexed clockwise
+/// viewed from the top in both layers, the slice goes between slots 11 and
+/// 0 and between slots 5 and 6
+type Layer = [u8; 12];
+
+/// Top layer of the solved puzzle, corner, corner and edge slots repeat
+const SOLVED_TOP: Layer = [0, 0, 1, 2, 2, 3, 4, 4, 5, 6, 6, 7];
+
+/// Bottom layer of the solved puzzle
+const SOLVED_BOTTOM: Layer = [8, 8, 9, 10, 10, 11, 12, 12, 13, 14, 14, 15];
+
+/// Generates Square-1 scramble in the slash notation. Each twist `(a,b)`
+/// turns the top layer by `a` and the bottom layer by `b` twelfths, only
+/// twists after which the slice can be turned are picked
+pub fn generate(rng: &mut impl Rng) -> String {
+    let (mut top, mut bottom) = (SOLVED_TOP, SOLVED_BOTTOM);
+
+    let mut moves = Vec::with_capacity(SLASHES);
+    for _ in 0..SLASHES {
+        let twists: Vec<(i32, i32)> = (-5..=6)
+            .flat_map(|a| (-5..=6).map(move |b| (a, b)))
+            .filter(|&(a, b)| {
+                (a, b) != (0, 0)
+                    && can_slash(&turn(&top, a))
+                    && can_slash(&turn(&bottom, -b))
+            })
+            .collect();
+        let Some(&(a, b)) = twists.choose(rng) else {
+            break;
+        };
+
+        top = turn(&top, a);
+        // Bottom is turned clockwise viewed from the bottom
+        bottom = turn(&bottom, -b);
+        slash(&mut top, &mut bottom);
+        moves.push(format!("({a},{b}) /"));
+    }
+    moves.join(" ")
+}
+
+/// Turns the layer clockwise by given number of slots
+fn turn(layer: &Layer, amount: i32) -> Layer {
+    let mut turned = [0; 12];
+    for (i, piece) in layer.iter().enumerate() {
+        turned[(i as i32 + amount).rem_euclid(12) as usize] = *piece;
+    }
+    turned
+}
+
+/// Checks whether the slice can be turned, which is when no corner lies
+/// across the slice
+fn can_slash(layer: &Layer) -> bool {
+    layer[11] != layer[0] && layer[5] != layer[6]
+}
+
+/// Turns the right half of the puzzle by 180 degrees, which swaps right
+/// halves of the layers and reverses their order
+fn slash(top: &mut Layer, bottom: &mut Layer) {
+    let (old_top, old_bottom) = (*top, *bottom);
+    for i in 0..6 {
+        top[5 - i] = old_bottom[i];
+        bottom[5 - i] = old_top[i];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{can_slash, generate, slash, turn, SOLVED_BOTTOM, SOLVED_TOP};
+
+    #[test]
+    fn twists_are_sliceable() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let scramble = generate(&mut rng);
+            let (mut top, mut bottom) = (SOLVED_TOP, SOLVED_BOTTOM);
+            for twist in scramble.split(" /").filter(|t| !t.is_empty()) {
+                let (a, b) = twist
+                    .trim()
+                    .strip_prefix('(')
+                    .and_then(|t| t.strip_suffix(')'))
+                    .and_then(|t| t.split_once(','))
+                    .expect("twist in the (a,b) format");
+                top = turn(&top, a.parse().unwrap());
+                bottom = turn(&bottom, -b.parse::<i32>().unwrap());
+                assert!(can_slash(&top), "{twist} in {scramble}");
+                assert!(can_slash(&bottom), "{twist} in {scramble}");
+                slash(&mut top, &mut bottom);
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crossterm::event::KeyCode;
+use termint::{
+    enums::Color,
+    geometry::Constraint,
+    style::Style,
+    widgets::{Block, Layout, List, ListState, Spacer, StrSpanExtension},
+};
+
+use crate::{
+    app::App, error::Error, scramble::Scramble, widgets::raw_span::RawSpan,
+};
+
+/// Dialogs for managing sessions in the session picker
+#[derive(Debug, Clone)]
+pub enum SessionDialog {
+    /// Entering name of new session or new name of the renamed session
+    Name {
+        rename: Option<String>,
+        error: Option<String>,
+    },
+    /// Picking scramble type of the new session
+    ScrambleType {
+        name: String,
+        state: Rc<RefCell<ListState>>,
+    },
+    /// Confirming removal of the session
+    Delete { name: String },
+}
+
+/// Session dialogs implementation
+impl App {
+    /// Opens dialog for creating new session
+    pub fn create_session_dialog(&mut self) {
+        self.input.clear();
+        self.session_dialog = Some(SessionDialog::Name {
+            rename: None,
+            error: None,
+        });
+    }
+
+    /// Opens dialog for renaming the given session
+    pub fn rename_session_dialog(&mut self, name: String) {
+        self.input = name.clone();
+        self.session_dialog = Some(SessionDialog::Name {
+            rename: Some(name),
+            error: None,
+        });
+    }
+
+    /// Opens dialog confirming removal of the given session
+    pub fn delete_session_dialog(&mut self, name: String) {
+        self.session_dialog = Some(SessionDialog::Delete { name });
+    }
+
+    /// Listens to pressed keys while session dialog is opened
+    pub fn listen_session_dialog(
+        &mut self,
+        code: KeyCode,
+    ) -> Result<(), Error> {
+        let Some(dialog) = self.session_dialog.take() else {
+            return Ok(());
+        };
+
+        self.session_dialog = match (dialog, code) {
+            (_, KeyCode::Esc) => None,
+            (SessionDialog::Name { rename, .. }, KeyCode::Enter) => {
+                self.confirm_name(rename)?
+            }
+            (dialog @ SessionDialog::Name { .. }, KeyCode::Char(c)) => {
+                self.input.push(c);
+                Some(dialog)
+            }
+            (dialog @ SessionDialog::Name { .. }, KeyCode::Backspace) => {
+                self.input.pop();
+                Some(dialog)
+            }
+            (SessionDialog::ScrambleType { name, state }, KeyCode::Enter) => {
+                let sel = state.borrow().selected.unwrap_or_default();
+                self.stats.add_session(&name, Scramble::TYPES[sel])?;
+                self.stats.save()?;
+                self.select_session(&name);
+                None
+            }
+            (
+                dialog @ SessionDialog::ScrambleType { .. },
+                KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K'),
+            ) => {
+                if let SessionDialog::ScrambleType { state, .. } = &dialog {
+                    let mut state = state.borrow_mut();
+                    if let Some(sel) = state.selected {
+                        state.selected = Some(sel.saturating_sub(1));
+                    }
+                }
+                Some(dialog)
+            }
+            (
+                dialog @ SessionDialog::ScrambleType { .. },
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J'),
+            ) => {
+                if let SessionDialog::ScrambleType { state, .. } = &dialog {
+                    let mut state = state.borrow_mut();
+                    if let Some(sel) = state.selected {
+                        if sel + 1 < Scramble::TYPES.len() {
+                            state.selected = Some(sel + 1);
+                        }
+                    }
+                }
+                Some(dialog)
+            }
+            (
+                SessionDialog::Delete { name },
+                KeyCode::Char('y') | KeyCode::Char('Y'),
+            ) => {
+                self.stats.remove_session(&name)?;
+                self.stats.save()?;
+                if self.session.as_ref() == Some(&name) {
+                    self.session = None;
+                    self.scramble = None;
+                }
+                let len = self.stats.sessions.len();
+                let mut state = self.sessions_state.borrow_mut();
+                if let Some(sel) = state.selected {
+                    state.selected = Some(sel.min(len.saturating_sub(1)));
+                }
+                None
+            }
+            (
+                SessionDialog::Delete { .. },
+                KeyCode::Char('n') | KeyCode::Char('N'),
+            ) => None,
+            (dialog, _) => Some(dialog),
+        };
+        self.render()
+    }
+
+    /// Gets layout of the opened session dialog
+    pub fn session_dialog_layout(&self) -> Layout {
+        let mut layout = Layout::horizontal().center();
+        let Some(dialog) = &self.session_dialog else {
+            return layout;
+        };
+
+        let block = match dialog {
+            SessionDialog::Name { rename, error } => {
+                let title = match rename {
+                    Some(_) => "Rename session",
+                    None => "New session",
+                };
+                let mut block = Block::vertical().title(title);
+                block.add_child("Name:".fg(Color::Gray), Constraint::Min(0));
+                block.add_child(
+                    RawSpan::new(format!("{}█", self.input)),
+                    Constraint::Length(1),
+                );
+                if let Some(error) = error {
+                    block.add_child(
+                        error.as_str().fg(Color::Red),
+                        Constraint::Min(0),
+                    );
+                }
+                block
+            }
+            SessionDialog::ScrambleType { name, state } => {
+                let title = format!("New session {name}");
+                let mut block = Block::vertical().title(title.as_str());
+                block.add_child(
+                    "Scramble type:".fg(Color::Gray),
+                    Constraint::Min(0),
+                );
+                block.add_child(
+                    List::new(Scramble::TYPES.to_vec(), state.clone())
+                        .selected_style(Style::new().fg(Color::DarkCyan))
+                        .auto_scroll(),
+                    Constraint::Fill,
+                );
+                block
+            }
+            SessionDialog::Delete { name } => {
+                let solves = self
+                    .stats
+                    .get_session(name)
+                    .map(|s| s.stats.len())
+                    .unwrap_or_default();
+                let mut block = Block::vertical().title("Delete session");
+                block.add_child(
+                    format!("Delete session {name} with {solves} solves?"),
+                    Constraint::Min(0),
+                );
+                block.add_child(Spacer::new(), Constraint::Fill);
+                block.add_child(
+                    "This can't be undone!".fg(Color::Red),
+                    Constraint::Min(0),
+                );
+                block
+            }
+        };
+        layout.add_child(block, Constraint::Length(40));
+        layout
+    }
+
+    /// Gets help layout of the opened session dialog
+    pub fn session_dialog_help(&self) -> Layout {
+        let mut layout = Layout::horizontal();
+        let help: &[&str] = match self.session_dialog {
+            Some(SessionDialog::Name { .. }) => {
+                &["[Enter]Confirm ", "[Esc]Cancel "]
+            }
+            Some(SessionDialog::ScrambleType { .. }) => &[
+                "[↑|k]Move up ",
+                "[↓/j]Move down ",
+                "[Enter]Create session ",
+                "[Esc]Cancel ",
+            ],
+            Some(SessionDialog::Delete { .. }) => {
+                &["[y]Delete ", "[n|Esc]Cancel "]
+            }
+            None => &[],
+        };
+        for item in help {
+            layout.add_child(
+                RawSpan::new(item).fg(Color::Gray),
+                Constraint::Min(0),
+            );
+        }
+        layout
+    }
+
+    /// Confirms entered session name, creates the session or renames it
+    ///
+    /// **Returns:**
+    /// * Dialog which should be opened next
+    fn confirm_name(
+        &mut self,
+        rename: Option<String>,
+    ) -> Result<Option<SessionDialog>, Error> {
+        let name = self.input.trim().to_owned();
+        let res = match &rename {
+            Some(old) => self.stats.rename_session(old, &name),
+            None if name.is_empty() => {
+                Err(Error::Msg("session name can't be empty".to_string()))
+            }
+            None if self.stats.exists(&name) => Err(Error::Msg(
+                "session with this name already exists".to_string(),
+            )),
+            None => {
+                return Ok(Some(SessionDialog::ScrambleType {
+                    name,
+                    state: Rc::new(RefCell::new(ListState::selected(0, 0))),
+                }))
+            }
+        };
+
+        if let Err(e) = res {
+            return Ok(Some(SessionDialog::Name {
+                rename,
+                error: Some(e.to_string()),
+            }));
+        }
+
+        self.stats.save()?;
+        if self.session.as_ref() == rename.as_ref() {
+            self.session = Some(name.clone());
+        }
+        self.select_session(&name);
+        Ok(None)
+    }
+
+    /// Selects session with given name in the session picker
+    pub fn select_session(&mut self, name: &str) {
+        if let Some(pos) = self
+            .stats
+            .get_sessions(self.config.session_sort)
+            .iter()
+            .position(|s| s == name)
+        {
+            self.sessions_state.borrow_mut().selected = Some(pos);
+        }
+    }
+}
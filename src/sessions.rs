@@ -10,6 +10,7 @@ use crate::{
     app::{App, Screen},
     error::Error,
     scramble::Scramble,
+    stats::session_sort::SessionSort,
     widgets::raw_span::RawSpan,
 };
 
@@ -20,8 +21,21 @@ impl App {
         layout.add_child(Spacer::new(), Constraint::Fill);
         layout.add_child(self.title(), Constraint::Length(6));
         layout.add_child(Spacer::new(), Constraint::Length(1));
-        layout.add_child(self.list(), Constraint::Fill);
-        layout.add_child(self.sessions_help(), Constraint::Length(1));
+        if self.session_dialog.is_some() {
+            layout.add_child(
+                self.session_dialog_layout(),
+                Constraint::Length(7),
+            );
+            layout.add_child(Spacer::new(), Constraint::Fill);
+            layout
+                .add_child(self.session_dialog_help(), Constraint::Length(1));
+        } else {
+            layout.add_child(self.list(), Constraint::Fill);
+            if let Some(warning) = self.instance_warning() {
+                layout.add_child(warning, Constraint::Length(1));
+            }
+            layout.add_child(self.sessions_help(), Constraint::Length(1));
+        }
 
         self.term.render(layout)?;
         Ok(())
@@ -29,6 +43,10 @@ impl App {
 
     /// Listens to pressed keys while showing Sessions screen
     pub fn listen_sessions(&mut self, code: KeyCode) -> Result<(), Error> {
+        if self.session_dialog.is_some() {
+            return self.listen_session_dialog(code);
+        }
+
         match code {
             KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => {
                 {
@@ -59,7 +77,7 @@ impl App {
 
                 let session = self
                     .stats
-                    .get_sessions()
+                    .get_sessions(self.config.session_sort)
                     .get(sel)
                     .ok_or(Error::Msg("getting session".to_string()))?
                     .to_string();
@@ -73,11 +91,40 @@ impl App {
                 scramble.generate();
                 self.scramble = Some(scramble);
 
+                self.stats.mark_used(&session);
+                self.stats.save()?;
                 self.session = Some(session);
                 self.screen = Screen::Timer;
 
                 self.render_timer()
             }
+            KeyCode::Char('-') => self.move_session(-1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.move_session(1),
+            KeyCode::Char('o') | KeyCode::Char('O') => {
+                let selected = self.selected_session();
+                self.config
+                    .set_session_sort(self.config.session_sort.next())?;
+                if let Some(name) = selected {
+                    self.select_session(&name);
+                }
+                self.render()
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.create_session_dialog();
+                self.render()
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                if let Some(name) = self.selected_session() {
+                    self.rename_session_dialog(name);
+                }
+                self.render()
+            }
+            KeyCode::Delete | KeyCode::Char('d') | KeyCode::Char('D') => {
+                if let Some(name) = self.selected_session() {
+                    self.delete_session_dialog(name);
+                }
+                self.render()
+            }
             KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
                 Err(Error::Exit)
             }
@@ -85,6 +132,30 @@ impl App {
         }
     }
 
+    /// Moves selected session by given offset, works only with custom order
+    fn move_session(&mut self, offset: isize) -> Result<(), Error> {
+        if self.config.session_sort != SessionSort::Custom {
+            return Ok(());
+        }
+        let Some(name) = self.selected_session() else {
+            return Ok(());
+        };
+
+        self.stats.move_session(&name, offset);
+        self.stats.save()?;
+        self.select_session(&name);
+        self.render()
+    }
+
+    /// Gets name of the currently selected session
+    fn selected_session(&self) -> Option<String> {
+        let sel = self.sessions_state.borrow().selected?;
+        self.stats
+            .get_sessions(self.config.session_sort)
+            .get(sel)
+            .cloned()
+    }
+
     /// Inserts title to the given block
     fn title(&self) -> Layout {
         let title = format!(
@@ -107,7 +178,7 @@ impl App {
 
     fn list(&self) -> Layout {
         let mut layout = Layout::horizontal().center();
-        let keys = self.stats.get_sessions();
+        let keys = self.stats.get_sessions(self.config.session_sort);
         if keys.is_empty() {
             layout.add_child("No sessions...", Constraint::Min(0));
         } else {
@@ -136,6 +207,32 @@ impl App {
             RawSpan::new("[Enter]Select session ").fg(Color::Gray),
             Constraint::Min(0),
         );
+        if self.config.session_sort == SessionSort::Custom {
+            layout.add_child(
+                RawSpan::new("[-|+]Move session ").fg(Color::Gray),
+                Constraint::Min(0),
+            );
+        }
+        layout.add_child(
+            RawSpan::new(format!(
+                "[o]Sort: {} ",
+                self.config.session_sort.name()
+            ))
+            .fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[a]Add ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[r]Rename ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[Del|d]Delete ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
         layout.add_child(
             RawSpan::new("[Esc|q]Quit ").fg(Color::Gray),
             Constraint::Min(0),
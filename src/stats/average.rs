@@ -0,0 +1,144 @@
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, stats::stat::SolveTime};
+
+/// Statistic computed from the last n solves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Average {
+    /// Mean of n, no solves are trimmed
+    Mean(usize),
+    /// Average of n, ceil(5%) of the solves are trimmed from each end
+    Avg(usize),
+}
+
+impl Average {
+    /// Averages that can be picked in the session settings
+    pub const PRESETS: [Average; 6] = [
+        Average::Mean(3),
+        Average::Avg(5),
+        Average::Avg(12),
+        Average::Avg(50),
+        Average::Avg(100),
+        Average::Avg(1000),
+    ];
+
+    /// Gets number of solves the statistic is computed from
+    pub fn size(&self) -> usize {
+        match self {
+            Average::Mean(n) | Average::Avg(n) => *n,
+        }
+    }
+
+    /// Gets number of solves trimmed from each end
+    pub fn trim(&self) -> usize {
+        match self {
+            Average::Mean(_) => 0,
+            Average::Avg(n) => n.div_ceil(20),
+        }
+    }
+
+    /// Computes the statistic from the given times. DNF is counted as the
+    /// worst time and the result is DNF when DNFs can't be all trimmed
+    ///
+    /// **Returns:**
+    /// * Computed statistic, [`None`] when number of times doesn't match
+    pub fn compute(&self, times: &[SolveTime]) -> Option<SolveTime> {
+        if times.len() != self.size() {
+            return None;
+        }
+
+        let mut times = times.to_vec();
+        times.sort();
+        self.trimmed(&times)
+    }
+
+    /// Gets the best statistic out of all consecutive windows of the given
+    /// times. Uses sorted sliding window, so it doesn't sort every window
+    ///
+    /// **Returns:**
+    /// * Best statistic, [`None`] when there's not enough times
+    pub fn best(&self, times: &[SolveTime]) -> Option<SolveTime> {
+        let n = self.size();
+        if times.len() < n {
+            return None;
+        }
+
+        let mut window = times[..n].to_vec();
+        window.sort();
+        let mut best = self.trimmed(&window)?;
+
+        for i in n..times.len() {
+            if let Ok(pos) = window.binary_search(&times[i - n]) {
+                window.remove(pos);
+            }
+            let pos = window.binary_search(&times[i]).unwrap_or_else(|p| p);
+            window.insert(pos, times[i]);
+            best = best.min(self.trimmed(&window)?);
+        }
+        Some(best)
+    }
+
+    /// Computes the statistic from the given sorted times
+    fn trimmed(&self, sorted: &[SolveTime]) -> Option<SolveTime> {
+        let n = self.size();
+        let trim = self.trim();
+        if sorted.len() != n || n <= 2 * trim {
+            return None;
+        }
+
+        let mut total = Duration::ZERO;
+        for time in &sorted[trim..(n - trim)] {
+            match time {
+                SolveTime::Time(time) => total += *time,
+                SolveTime::Dnf => return Some(SolveTime::Dnf),
+            }
+        }
+        Some(SolveTime::Time(total / (n - 2 * trim) as u32))
+    }
+}
+
+impl Display for Average {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Average::Mean(n) => write!(f, "mo{n}"),
+            Average::Avg(n) => write!(f, "ao{n}"),
+        }
+    }
+}
+
+impl FromStr for Average {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (avg, n) = if let Some(n) = lower.strip_prefix("mo") {
+            (Average::Mean(n.parse().unwrap_or(0)), n)
+        } else if let Some(n) = lower.strip_prefix("ao") {
+            (Average::Avg(n.parse().unwrap_or(0)), n)
+        } else {
+            return Err(Error::Msg(format!("invalid average: {s}")));
+        };
+
+        if avg.size() <= 2 * avg.trim() {
+            return Err(Error::Msg(format!("invalid average size: {n}")));
+        }
+        Ok(avg)
+    }
+}
+
+impl TryFrom<String> for Average {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Average> for String {
+    fn from(value: Average) -> Self {
+        value.to_string()
+    }
+}
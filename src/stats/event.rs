@@ -0,0 +1,97 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::stats::stat::{Penalty, Stat};
+
+/// Identifies solve the event changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SolveId {
+    /// Unique id of the solve
+    Id(u64),
+    /// Date and time of the solve, used by journals written before solves
+    /// had ids
+    Datetime(DateTime<Local>),
+}
+
+/// Change of the stats, which wasn't saved yet. Solves are identified by
+/// their ids, so that applying events is idempotent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Event {
+    Add {
+        session: String,
+        stat: Stat,
+    },
+    Remove {
+        session: String,
+        id: SolveId,
+    },
+    Penalty {
+        session: String,
+        id: SolveId,
+        penalty: Penalty,
+    },
+    Comment {
+        session: String,
+        id: SolveId,
+        comment: String,
+    },
+    CreateSession {
+        session: String,
+        scramble_type: String,
+    },
+    RenameSession {
+        session: String,
+        name: String,
+    },
+    RemoveSession {
+        session: String,
+    },
+    /// Settings of the session changed (inspection, averages, last use),
+    /// current settings are taken from the stats when saving
+    UpdateSession {
+        session: String,
+    },
+    /// User defined order of the sessions changed
+    Reorder,
+}
+
+impl Event {
+    /// Checks whether the event changes solves only (session changes are
+    /// not journaled)
+    pub fn is_solve(&self) -> bool {
+        matches!(
+            self,
+            Event::Add { .. }
+                | Event::Remove { .. }
+                | Event::Penalty { .. }
+                | Event::Comment { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, SolveId};
+
+    #[test]
+    fn solve_id() {
+        let event = Event::Remove {
+            session: "3x3".to_owned(),
+            id: SolveId::Id(42),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"Remove":{"session":"3x3","id":42}}"#);
+    }
+
+    #[test]
+    fn legacy_solve_id() {
+        let json = r#"{"Remove":{"session":"3x3",
+            "id":"2024-03-01T18:21:40.987654321+01:00"}}"#;
+        let event: Event = serde_json::from_str(json).unwrap();
+        let Event::Remove { id, .. } = event else {
+            panic!("unexpected event {event:?}");
+        };
+        assert!(matches!(id, SolveId::Datetime(_)));
+    }
+}
@@ -1,4 +1,9 @@
+pub mod average;
+pub mod event;
+pub mod overview;
+pub mod records;
 pub mod session;
+pub mod session_sort;
 pub mod stat;
 #[allow(clippy::module_inception)]
 pub mod stats;
@@ -0,0 +1,170 @@
+use std::{str::FromStr, time::Duration};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::Error,
+    stats::{average::Average, stat::SolveTime, stats::Stats},
+};
+
+/// Averages shown in the overview
+const AVERAGES: [Average; 3] =
+    [Average::Avg(5), Average::Avg(12), Average::Avg(100)];
+
+/// Number of sub-X thresholds used when none are given
+const SUB_COUNT: u64 = 3;
+
+/// Format of the printed overview
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Output {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Output {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            _ => Err(Error::Msg(format!("unknown output format '{s}'"))),
+        }
+    }
+}
+
+/// Overview of the session statistics
+#[derive(Debug)]
+pub struct Overview {
+    pub session: String,
+    pub scramble_type: String,
+    pub solves: usize,
+    pub dnfs: usize,
+    pub mean: Option<SolveTime>,
+    /// Current and best single
+    pub single: (Option<SolveTime>, Option<SolveTime>),
+    /// Current and best value of each average
+    pub averages: Vec<(Average, Option<SolveTime>, Option<SolveTime>)>,
+    /// Number of solves faster than given number of seconds
+    pub sub: Vec<(f64, usize)>,
+}
+
+impl Overview {
+    /// Computes [`Overview`] of the given session
+    ///
+    /// **Parameters:**
+    /// * `stats` - stats containing the session
+    /// * `session` - name of the session
+    /// * `sub` - thresholds of the sub-X counts in seconds, when empty,
+    ///   thresholds are picked based on the session mean
+    ///
+    /// **Returns:**
+    /// * Computed [`Overview`], [`None`] when session doesn't exist
+    pub fn new(stats: &Stats, session: &str, sub: &[f64]) -> Option<Self> {
+        let info = stats.get_session(session)?;
+        let times: Vec<SolveTime> =
+            info.stats.iter().map(|s| s.solve_time()).collect();
+        let mean = stats.avg(session);
+
+        let sub = match (sub, mean) {
+            ([], Some(SolveTime::Time(mean))) => {
+                let max = mean.as_secs() + 1;
+                (max.saturating_sub(SUB_COUNT - 1).max(1)..=max)
+                    .map(|s| s as f64)
+                    .collect()
+            }
+            _ => sub.to_vec(),
+        };
+        let sub = sub
+            .into_iter()
+            .map(|s| {
+                let limit = SolveTime::Time(
+                    Duration::try_from_secs_f64(s).unwrap_or_default(),
+                );
+                (s, times.iter().filter(|t| **t < limit).count())
+            })
+            .collect();
+
+        Some(Self {
+            session: session.to_owned(),
+            scramble_type: info.scramble_type.clone(),
+            solves: times.len(),
+            dnfs: times.iter().filter(|t| **t == SolveTime::Dnf).count(),
+            mean,
+            single: (times.first().copied(), times.iter().min().copied()),
+            averages: AVERAGES
+                .iter()
+                .map(|a| (*a, info.average_at(0, *a), a.best(&times)))
+                .collect(),
+            sub,
+        })
+    }
+
+    /// Prints the [`Overview`] in human readable form
+    pub fn print(&self) {
+        let time = |t: Option<SolveTime>| {
+            t.map_or("-".to_string(), |t| t.to_string())
+        };
+        let current = |name: &str, (cur, best)| {
+            println!(
+                "  {name:<8}{} \x1b[90m(best {})\x1b[0m",
+                time(cur),
+                time(best)
+            );
+        };
+
+        println!(
+            "\x1b[93m{}\x1b[0m (scramble type: {})",
+            self.session, self.scramble_type
+        );
+        println!("  {:<8}{} ({} DNF)", "solves", self.solves, self.dnfs);
+        println!("  {:<8}{}", "mean", time(self.mean));
+        current("single", self.single);
+        for (average, cur, best) in &self.averages {
+            current(&average.to_string(), (*cur, *best));
+        }
+        for (limit, count) in &self.sub {
+            println!("  {:<8}{count}", format!("sub-{limit}"));
+        }
+    }
+
+    /// Converts the [`Overview`] to JSON. Times are in seconds, DNF is
+    /// `"DNF"` and missing values are `null`
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "session": self.session,
+            "scramble_type": self.scramble_type,
+            "solves": self.solves,
+            "dnfs": self.dnfs,
+            "mean": time_json(self.mean),
+            "single": record_json(self.single),
+        });
+        for (average, cur, best) in &self.averages {
+            value[average.to_string()] = record_json((*cur, *best));
+        }
+        let sub: Map<String, Value> = self
+            .sub
+            .iter()
+            .map(|(limit, count)| (limit.to_string(), json!(count)))
+            .collect();
+        value["sub"] = Value::Object(sub);
+        value
+    }
+}
+
+/// Converts current and best value to JSON
+fn record_json(
+    (current, best): (Option<SolveTime>, Option<SolveTime>),
+) -> Value {
+    json!({ "current": time_json(current), "best": time_json(best) })
+}
+
+/// Converts solve time to JSON
+fn time_json(time: Option<SolveTime>) -> Value {
+    match time {
+        Some(SolveTime::Time(time)) => json!(time.as_secs_f64()),
+        Some(SolveTime::Dnf) => json!("DNF"),
+        None => Value::Null,
+    }
+}
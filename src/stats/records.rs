@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use crate::stats::{
+    average::Average,
+    stat::{SolveTime, Stat},
+};
+
+/// Kind of the personal best
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Record {
+    Single,
+    Average(Average),
+}
+
+/// Personal bests of the session - best single and best rolling averages
+#[derive(Debug, Clone, Default)]
+pub struct Records {
+    single: Option<SolveTime>,
+    averages: Vec<(Average, SolveTime)>,
+}
+
+impl Records {
+    /// Computes [`Records`] from all the given stats
+    ///
+    /// **Parameters:**
+    /// * `stats` - stats to compute the records from (newest first)
+    /// * `averages` - averages to find the best rolling value of
+    pub fn compute(stats: &[Stat], averages: &[Average]) -> Records {
+        let times: Vec<SolveTime> =
+            stats.iter().map(|s| s.solve_time()).collect();
+        Records {
+            single: times.iter().min().copied(),
+            averages: averages
+                .iter()
+                .filter_map(|a| Some((*a, a.best(&times)?)))
+                .collect(),
+        }
+    }
+
+    /// Updates [`Records`] with the newest stat, which is expected to be
+    /// already added to the stats
+    ///
+    /// **Parameters:**
+    /// * `stats` - stats of the session (newest first)
+    /// * `averages` - averages to find the best rolling value of
+    ///
+    /// **Returns:**
+    /// * Records the newest stat has beaten
+    pub fn update(
+        &mut self,
+        stats: &[Stat],
+        averages: &[Average],
+    ) -> Vec<Record> {
+        let mut beaten = vec![];
+        let Some(newest) = stats.first() else {
+            return beaten;
+        };
+
+        let time = newest.solve_time();
+        match self.single {
+            Some(best) if time < best => {
+                beaten.push(Record::Single);
+                self.single = Some(time);
+            }
+            None => self.single = Some(time),
+            _ => {}
+        }
+
+        for average in averages {
+            let times: Vec<SolveTime> = stats
+                .iter()
+                .take(average.size())
+                .map(|s| s.solve_time())
+                .collect();
+            let Some(value) = average.compute(&times) else {
+                continue;
+            };
+
+            match self.averages.iter_mut().find(|(a, _)| a == average) {
+                Some((_, best)) if value < *best => {
+                    beaten.push(Record::Average(*average));
+                    *best = value;
+                }
+                Some(_) => {}
+                None => self.averages.push((*average, value)),
+            }
+        }
+        beaten
+    }
+
+    /// Gets the best single
+    pub fn single(&self) -> Option<SolveTime> {
+        self.single
+    }
+
+    /// Gets the best value of the given [`Average`]
+    pub fn average(&self, average: Average) -> Option<SolveTime> {
+        self.averages
+            .iter()
+            .find(|(a, _)| *a == average)
+            .map(|(_, best)| *best)
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Record::Single => write!(f, "single"),
+            Record::Average(average) => write!(f, "{average}"),
+        }
+    }
+}
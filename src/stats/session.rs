@@ -1,12 +1,26 @@
+use chrono::{DateTime, Local, TimeDelta};
 use serde::{Deserialize, Serialize};
 
-use crate::stats::stat::Stat;
+use crate::stats::{
+    average::Average,
+    event::SolveId,
+    records::{Record, Records},
+    stat::{Penalty, SolveTime, Stat},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 /// Session struct to store its stats and scramble type
 pub struct Session {
     pub scramble_type: String,
+    #[serde(default)]
+    pub inspection: bool,
+    #[serde(default = "Session::default_averages")]
+    pub averages: Vec<Average>,
+    #[serde(default)]
+    pub last_used: Option<DateTime<Local>>,
     pub stats: Vec<Stat>,
+    #[serde(skip)]
+    records: Records,
 }
 
 impl Session {
@@ -20,7 +34,11 @@ impl Session {
     pub fn new(scramble_type: &str) -> Session {
         Session {
             scramble_type: scramble_type.to_owned(),
+            inspection: false,
+            averages: Session::default_averages(),
+            last_used: None,
             stats: Vec::new(),
+            records: Records::default(),
         }
     }
 
@@ -28,8 +46,72 @@ impl Session {
     ///
     /// **Parameters:**
     /// * `stat` - [`Stat`] to be stored in [`Session`]
-    pub fn add(&mut self, stat: Stat) {
+    ///
+    /// **Returns:**
+    /// * Personal bests the [`Stat`] has beaten
+    pub fn add(&mut self, stat: Stat) -> Vec<Record> {
         self.stats.insert(0, stat);
+        self.records.update(&self.stats, &self.averages)
+    }
+
+    /// Inserts [`Stat`] keeping the stats sorted from the newest. Personal
+    /// bests aren't updated, so that more stats can be inserted at once
+    ///
+    /// **Parameters:**
+    /// * `stat` - [`Stat`] to be inserted
+    ///
+    /// **Returns:**
+    /// * Whether the [`Stat`] was inserted, [`Stat`] with the same id is
+    ///   already in the [`Session`] otherwise
+    pub fn insert(&mut self, mut stat: Stat) -> bool {
+        let index = self.stats.partition_point(|s| s.datetime > stat.datetime);
+        let mut same = self.stats[index..]
+            .iter()
+            .take_while(|s| s.datetime == stat.datetime);
+        if stat.id != 0 {
+            if same.any(|s| s.id == stat.id) {
+                return false;
+            }
+        } else {
+            // Solve saved before solves had ids
+            if same.any(|s| {
+                s.time == stat.time && s.scramble() == stat.scramble()
+            }) {
+                return false;
+            }
+            stat.id = self.free_id(Stat::legacy_id(&stat.datetime));
+        }
+        self.stats.insert(index, stat);
+        true
+    }
+
+    /// Gets the first id starting from the given one, which isn't used by
+    /// any [`Stat`] in the [`Session`]
+    fn free_id(&self, mut id: u64) -> u64 {
+        while self.stats.iter().any(|s| s.id == id) {
+            id += 1;
+        }
+        id
+    }
+
+    /// Checks whether the [`Stat`] is already in the [`Session`]. Stats are
+    /// compared with the precision of the formats they might've been
+    /// exported to (seconds for the date and milliseconds for the time),
+    /// penalty and scramble have to match too, since distinct solves can
+    /// be set in the same second
+    pub fn contains(&self, stat: &Stat) -> bool {
+        let second = TimeDelta::try_seconds(1).unwrap_or_default();
+        let start = self
+            .stats
+            .partition_point(|s| s.datetime >= stat.datetime + second);
+        self.stats[start..]
+            .iter()
+            .take_while(|s| s.datetime > stat.datetime - second)
+            .any(|s| {
+                s.time.as_millis() == stat.time.as_millis()
+                    && s.penalty == stat.penalty
+                    && s.scramble() == stat.scramble()
+            })
     }
 
     /// Removes [`Stat`] from [`Session`]
@@ -39,6 +121,161 @@ impl Session {
     pub fn remove(&mut self, index: usize) {
         if index < self.stats.len() {
             self.stats.remove(index);
+            self.update_records();
+        }
+    }
+
+    /// Toggles whether given [`Average`] is shown in the [`Session`]
+    ///
+    /// **Parameters:**
+    /// * `average` - [`Average`] to be toggled
+    pub fn toggle_average(&mut self, average: Average) {
+        if let Some(pos) = self.averages.iter().position(|a| *a == average) {
+            self.averages.remove(pos);
+        } else {
+            self.averages.push(average);
+            self.averages
+                .sort_by_key(|a| (a.size(), matches!(a, Average::Avg(_))));
+        }
+        self.update_records();
+    }
+
+    /// Toggles penalty of the [`Stat`] on given index
+    ///
+    /// **Parameters:**
+    /// * `index` - index of [`Stat`] to toggle the penalty of
+    /// * `penalty` - [`Penalty`] to be toggled
+    pub fn toggle_penalty(&mut self, index: usize, penalty: Penalty) {
+        if let Some(stat) = self.stats.get_mut(index) {
+            stat.toggle_penalty(penalty);
+            self.update_records();
+        }
+    }
+
+    /// Sets comment of the [`Stat`] on given index
+    ///
+    /// **Parameters:**
+    /// * `index` - index of [`Stat`] to set the comment of
+    /// * `comment` - comment to be set
+    pub fn set_comment(&mut self, index: usize, comment: String) {
+        if let Some(stat) = self.stats.get_mut(index) {
+            stat.set_comment(comment);
         }
     }
+
+    /// Gets index of the [`Stat`] with given id
+    pub fn index_of(&self, id: SolveId) -> Option<usize> {
+        self.stats.iter().position(|s| match id {
+            SolveId::Id(id) => s.id == id,
+            SolveId::Datetime(datetime) => s.datetime == datetime,
+        })
+    }
+
+    /// Computes given [`Average`] of the solves ending with the [`Stat`] on
+    /// given index (the [`Stat`] and older ones)
+    ///
+    /// **Parameters:**
+    /// * `index` - index of the newest [`Stat`] in the average
+    /// * `average` - [`Average`] to be computed
+    pub fn average_at(
+        &self,
+        index: usize,
+        average: Average,
+    ) -> Option<SolveTime> {
+        let times: Vec<SolveTime> = self
+            .stats
+            .iter()
+            .skip(index)
+            .take(average.size())
+            .map(|s| s.solve_time())
+            .collect();
+        average.compute(&times)
+    }
+
+    /// Gets personal bests of the [`Session`]
+    pub fn records(&self) -> &Records {
+        &self.records
+    }
+
+    /// Recomputes personal bests from all the stats
+    pub fn update_records(&mut self) {
+        self.records = Records::compute(&self.stats, &self.averages);
+    }
+
+    /// Gets averages shown by default
+    fn default_averages() -> Vec<Average> {
+        vec![Average::Avg(5), Average::Avg(12)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Session;
+    use crate::stats::{
+        event::SolveId,
+        stat::{Penalty, Stat},
+    };
+
+    fn stat(millis: u64, scramble: &str) -> Stat {
+        Stat::new(
+            Duration::from_millis(millis),
+            Penalty::None,
+            scramble.to_owned(),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn insert_same_datetime() {
+        let mut session = Session::new("3x3x3");
+        let first = stat(10_000, "R U");
+        let mut second = stat(11_000, "F D");
+        second.datetime = first.datetime;
+
+        assert!(session.insert(first.clone()));
+        assert!(session.insert(second.clone()));
+        assert!(!session.insert(first.clone()));
+        assert_eq!(session.stats.len(), 2);
+        let first = session.index_of(SolveId::Id(first.id));
+        let second = session.index_of(SolveId::Id(second.id));
+        assert!(first.is_some() && second.is_some() && first != second);
+    }
+
+    #[test]
+    fn insert_legacy() {
+        let mut session = Session::new("3x3x3");
+        let mut first = stat(10_000, "R U");
+        let mut second = stat(11_000, "F D");
+        first.id = 0;
+        second.id = 0;
+        second.datetime = first.datetime;
+
+        assert!(session.insert(first.clone()));
+        assert!(session.insert(second.clone()));
+        assert!(!session.insert(second));
+        assert_eq!(session.stats.len(), 2);
+        assert_ne!(session.stats[0].id, session.stats[1].id);
+        assert!(session.stats.iter().all(|s| s.id != 0));
+    }
+
+    #[test]
+    fn contains_same_second() {
+        let mut session = Session::new("3x3x3");
+        let first = stat(10_000, "R U");
+        session.insert(first.clone());
+
+        let mut other = stat(10_000, "F D");
+        other.datetime = first.datetime;
+        assert!(!session.contains(&other));
+
+        let mut penalty = first.clone();
+        penalty.penalty = Penalty::Dnf;
+        assert!(!session.contains(&penalty));
+
+        let mut exported = stat(10_000, "R U");
+        exported.datetime = first.datetime;
+        assert!(session.contains(&exported));
+    }
 }
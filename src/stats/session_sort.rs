@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Contains all the ways sessions can be sorted in the session picker
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum SessionSort {
+    /// User defined order, creation order by default
+    #[default]
+    Custom,
+    /// Most recently used sessions first
+    Recent,
+    /// Sorted alphabetically by name
+    Name,
+}
+
+impl SessionSort {
+    /// Gets next session sort
+    pub fn next(&self) -> SessionSort {
+        match self {
+            SessionSort::Custom => SessionSort::Recent,
+            SessionSort::Recent => SessionSort::Name,
+            SessionSort::Name => SessionSort::Custom,
+        }
+    }
+
+    /// Gets name of the session sort
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionSort::Custom => "custom",
+            SessionSort::Recent => "recent",
+            SessionSort::Name => "name",
+        }
+    }
+}
@@ -1,12 +1,37 @@
-use std::time::Duration;
+use std::{fmt::Display, time::Duration};
 
 use chrono::{DateTime, Local};
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 
+/// Penalty applied to the solve
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum Penalty {
+    #[default]
+    None,
+    PlusTwo,
+    Dnf,
+}
+
+/// Time of the solve with its penalty applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SolveTime {
+    Time(Duration),
+    Dnf,
+}
+
 /// Stat struct containing information about solve
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Stat {
+    /// Unique id of the solve in its session, 0 when the solve was saved
+    /// before solves had ids
+    #[serde(default)]
+    pub id: u64,
     pub time: Duration,
+    #[serde(default)]
+    pub penalty: Penalty,
     scramble: String,
     comment: String,
     pub datetime: DateTime<Local>,
@@ -14,12 +39,115 @@ pub struct Stat {
 
 impl Stat {
     /// Creates new [`Stat`] with given info about the solve
-    pub fn new(time: Duration, scramble: String, comment: String) -> Stat {
+    pub fn new(
+        time: Duration,
+        penalty: Penalty,
+        scramble: String,
+        comment: String,
+    ) -> Stat {
         Stat {
+            // Fits into SQLite integer, 0 is reserved for missing id
+            id: thread_rng().gen_range(1..=i64::MAX as u64),
             time,
+            penalty,
             scramble,
             comment,
             datetime: Local::now(),
         }
     }
+
+    /// Creates [`Stat`] of the stored solve, keeping its id and the date
+    /// and time it was set
+    #[cfg(feature = "sqlite")]
+    pub fn stored(
+        id: u64,
+        time: Duration,
+        penalty: Penalty,
+        scramble: String,
+        comment: String,
+        datetime: DateTime<Local>,
+    ) -> Stat {
+        Stat {
+            id,
+            time,
+            penalty,
+            scramble,
+            comment,
+            datetime,
+        }
+    }
+
+    /// Gets id of the solve saved before solves had ids. It's derived from
+    /// the date and time of the solve, so it's the same each time the solve
+    /// is loaded. Callers make it unique in the session
+    pub fn legacy_id(datetime: &DateTime<Local>) -> u64 {
+        let nanos = datetime.timestamp_nanos_opt().unwrap_or_default();
+        nanos.unsigned_abs().clamp(1, i64::MAX as u64)
+    }
+
+    /// Gets time of the solve with the penalty applied
+    pub fn solve_time(&self) -> SolveTime {
+        match self.penalty {
+            Penalty::None => SolveTime::Time(self.time),
+            Penalty::PlusTwo => {
+                SolveTime::Time(self.time + Duration::from_secs(2))
+            }
+            Penalty::Dnf => SolveTime::Dnf,
+        }
+    }
+
+    /// Gets scramble of the solve
+    pub fn scramble(&self) -> &str {
+        &self.scramble
+    }
+
+    /// Gets comment of the solve
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Sets comment of the solve
+    pub fn set_comment(&mut self, comment: String) {
+        self.comment = comment;
+    }
+
+    /// Toggles given penalty - sets it when not set, otherwise removes it
+    pub fn toggle_penalty(&mut self, penalty: Penalty) {
+        if self.penalty == penalty {
+            self.penalty = Penalty::None;
+        } else {
+            self.penalty = penalty;
+        }
+    }
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.penalty {
+            Penalty::None => write!(f, "{:.3}", self.time.as_secs_f64()),
+            Penalty::PlusTwo => write!(f, "{}+", self.solve_time()),
+            Penalty::Dnf => {
+                write!(f, "DNF({:.3})", self.time.as_secs_f64())
+            }
+        }
+    }
+}
+
+impl Display for Penalty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Penalty::None => write!(f, "None"),
+            Penalty::PlusTwo => write!(f, "+2"),
+            Penalty::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
+impl Display for SolveTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveTime::Time(time) => write!(f, "{:.3}", time.as_secs_f64()),
+            SolveTime::Dnf => write!(f, "DNF"),
+        }
+    }
 }
@@ -1,65 +1,285 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
-use dirs::config_dir;
+use chrono::Local;
 //use chrono::{offset, DateTime, Local};
 //use dirs::config_dir;
 use serde::{Deserialize, Serialize};
 
+use crate::config::Config;
 use crate::error::Error;
+use crate::stats::average::Average;
+use crate::stats::event::{Event, SolveId};
+use crate::stats::records::Record;
 use crate::stats::session::Session;
-use crate::stats::stat::Stat;
+use crate::stats::session_sort::SessionSort;
+use crate::stats::stat::{Penalty, SolveTime, Stat};
+use crate::storage::backups::{self, Backup};
+use crate::storage::{self, Storage};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Stats {
     pub sessions: HashMap<String, Session>,
+    /// User defined order of the sessions
+    #[serde(default)]
+    order: Vec<String>,
+    /// Whether saving is refused, because stats failed to load
+    #[serde(skip)]
+    locked: bool,
+    /// Changes not yet saved to the storage
+    #[serde(skip)]
+    pending: Vec<Event>,
+    /// Storage the stats were loaded from
+    #[serde(skip)]
+    storage: Option<Box<dyn Storage>>,
+    /// Whether changes saved by other instance were merged
+    #[serde(skip)]
+    merged: bool,
 }
 
 impl Stats {
-    /// Loads stats from JSON file
-    pub fn load() -> Stats {
-        match std::fs::read_to_string(
-            Stats::get_stats_dir().unwrap_or(".".to_string()),
-        ) {
-            Err(_) => Stats {
-                sessions: HashMap::new(),
-            },
-            Ok(s) => serde_json::from_str::<Stats>(&s).unwrap_or(Stats {
-                sessions: HashMap::new(),
-            }),
+    /// Loads stats from the storage backend set in the config
+    pub fn load(config: &Config) -> Result<Stats, Error> {
+        let mut storage = storage::open(config)?;
+        let mut stats = storage.load()?;
+        stats.storage = Some(storage);
+        stats.prepare();
+        Ok(stats)
+    }
+
+    /// Prepares loaded stats - fixes the order of the sessions and computes
+    /// personal bests
+    pub fn prepare(&mut self) {
+        self.normalize_order();
+        self.sessions.values_mut().for_each(Session::update_records);
+    }
+
+    /// Creates stats from given sessions, keeping their order
+    #[cfg(feature = "sqlite")]
+    pub fn from_sessions(sessions: Vec<(String, Session)>) -> Stats {
+        Stats {
+            order: sessions.iter().map(|(name, _)| name.clone()).collect(),
+            sessions: sessions.into_iter().collect(),
+            ..Default::default()
         }
     }
 
-    /// Saves stats to json file
-    pub fn save(&self) -> Result<(), Error> {
-        let filename = Stats::get_stats_dir()?;
-        let path = std::path::Path::new(&filename);
-        let prefix = path
-            .parent()
-            .ok_or(Error::Msg("Error creating stats directory".to_string()))?;
-        std::fs::create_dir_all(prefix)?;
-        std::fs::File::create(path)?;
+    /// Creates empty stats, which refuse to be saved until unlocked. Used
+    /// when stats failed to load
+    pub fn locked() -> Stats {
+        Stats {
+            locked: true,
+            ..Default::default()
+        }
+    }
 
-        let text = serde_json::to_string_pretty::<Stats>(self)?;
-        std::fs::write(path, text)?;
+    /// Allows saving stats, which failed to load (the user confirmed
+    /// starting with empty stats)
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
 
-        Ok(())
+    /// Saves changes of the stats to the storage
+    pub fn save(&mut self) -> Result<(), Error> {
+        if self.locked {
+            return Err(Error::Msg(
+                "stats failed to load, refusing to overwrite them".to_string(),
+            ));
+        }
+
+        let mut storage = match self.storage.take() {
+            Some(storage) => storage,
+            None => storage::open(&Config::load()?)?,
+        };
+        let pending = std::mem::take(&mut self.pending);
+        let res = storage.save(self, &pending);
+        if res.is_err() {
+            self.pending = pending;
+        }
+        self.storage = Some(storage);
+        res
     }
 
-    /// Adds given stat to the stats of given session
-    pub fn add(&mut self, stat: Stat, session: &str) -> Result<(), Error> {
-        if let Some(session) = self.sessions.get_mut(session) {
-            session.add(stat);
-            Ok(())
+    /// Merges stats saved by other instance with the local changes, which
+    /// weren't saved yet
+    ///
+    /// **Parameters:**
+    /// * `current` - stats currently saved in the storage
+    /// * `events` - local changes, which are applied on top of them
+    pub fn merge(&mut self, mut current: Stats, events: &[Event]) {
+        for event in events {
+            match event {
+                Event::CreateSession {
+                    session,
+                    scramble_type,
+                } => {
+                    if !current.exists(session) {
+                        current.sessions.insert(
+                            session.clone(),
+                            Session::new(scramble_type),
+                        );
+                        current.order.push(session.clone());
+                    }
+                }
+                Event::RenameSession { session, name } => {
+                    if current.exists(name) {
+                        continue;
+                    }
+                    if let Some(ses) = current.sessions.remove(session) {
+                        current.sessions.insert(name.clone(), ses);
+                    }
+                    for ses in current.order.iter_mut() {
+                        if ses == session {
+                            *ses = name.clone();
+                        }
+                    }
+                }
+                Event::RemoveSession { session } => {
+                    current.sessions.remove(session);
+                    current.order.retain(|s| s != session);
+                }
+                Event::UpdateSession { session } => {
+                    let local = self.sessions.get(session);
+                    let saved = current.sessions.get_mut(session);
+                    if let (Some(local), Some(saved)) = (local, saved) {
+                        saved.inspection = local.inspection;
+                        saved.averages = local.averages.clone();
+                        saved.last_used = local.last_used;
+                    }
+                }
+                Event::Reorder => current.order = self.order.clone(),
+                event => current.apply(event),
+            }
+        }
+
+        current.prepare();
+        self.sessions = current.sessions;
+        self.order = current.order;
+        self.merged = true;
+    }
+
+    /// Applies solve changes saved by other instance
+    pub fn merge_events(&mut self, events: &[Event]) {
+        events.iter().for_each(|e| self.apply(e));
+        self.sessions.values_mut().for_each(Session::update_records);
+        self.merged = true;
+    }
+
+    /// Checks whether changes saved by other instance were merged
+    pub fn merged(&self) -> bool {
+        self.merged
+    }
+
+    /// Gets all backups of the stats, newest first
+    pub fn backups(config: &Config) -> Result<Vec<Backup>, Error> {
+        let storage = storage::open(config)?;
+        Ok(backups::list(storage.path()))
+    }
+
+    /// Restores stats from the backup with given index. Current stats are
+    /// backed up first, so the restore can be reverted
+    pub fn restore(config: &Config, index: usize) -> Result<(), Error> {
+        storage::open(config)?.restore(index)
+    }
+
+    /// Adds given stat to the stats of given session, returns personal
+    /// bests the stat has beaten
+    pub fn add(
+        &mut self,
+        stat: Stat,
+        session: &str,
+    ) -> Result<Vec<Record>, Error> {
+        if let Some(ses) = self.sessions.get_mut(session) {
+            self.pending.push(Event::Add {
+                session: session.to_owned(),
+                stat: stat.clone(),
+            });
+            Ok(ses.add(stat))
         } else {
             Err(Error::Msg("non existing session".to_string()))
         }
     }
 
+    /// Imports given stats to the session, stats already in the session are
+    /// skipped
+    ///
+    /// **Returns:**
+    /// * Number of imported stats
+    pub fn import(
+        &mut self,
+        session: &str,
+        stats: Vec<Stat>,
+    ) -> Result<usize, Error> {
+        let ses = self
+            .sessions
+            .get_mut(session)
+            .ok_or(Error::Msg("non existing session".to_string()))?;
+
+        let mut count = 0;
+        for stat in stats {
+            if !ses.contains(&stat) && ses.insert(stat.clone()) {
+                self.pending.push(Event::Add {
+                    session: session.to_owned(),
+                    stat,
+                });
+                count += 1;
+            }
+        }
+        ses.update_records();
+        Ok(count)
+    }
+
     /// Removes [`Stat`] from given [`Session`]
     pub fn remove(&mut self, index: usize, session: &str) {
-        if let Some(session) = self.sessions.get_mut(session) {
-            session.remove(index);
+        let Some(ses) = self.sessions.get_mut(session) else {
+            return;
+        };
+        if let Some(stat) = ses.stats.get(index) {
+            self.pending.push(Event::Remove {
+                session: session.to_owned(),
+                id: SolveId::Id(stat.id),
+            });
+            ses.remove(index);
+        }
+    }
+
+    /// Toggles penalty of the [`Stat`] in given [`Session`]
+    pub fn toggle_penalty(
+        &mut self,
+        index: usize,
+        session: &str,
+        penalty: Penalty,
+    ) {
+        let Some(ses) = self.sessions.get_mut(session) else {
+            return;
+        };
+        ses.toggle_penalty(index, penalty);
+        if let Some(stat) = ses.stats.get(index) {
+            self.pending.push(Event::Penalty {
+                session: session.to_owned(),
+                id: SolveId::Id(stat.id),
+                penalty: stat.penalty,
+            });
+        }
+    }
+
+    /// Sets comment of the [`Stat`] in given [`Session`]
+    pub fn set_comment(
+        &mut self,
+        index: usize,
+        session: &str,
+        comment: String,
+    ) {
+        let Some(ses) = self.sessions.get_mut(session) else {
+            return;
+        };
+        if let Some(stat) = ses.stats.get(index) {
+            self.pending.push(Event::Comment {
+                session: session.to_owned(),
+                id: SolveId::Id(stat.id),
+                comment: comment.clone(),
+            });
+            ses.set_comment(index, comment);
         }
     }
 
@@ -69,24 +289,117 @@ impl Stats {
         session: &str,
         scramble_type: &str,
     ) -> Result<(), Error> {
-        if self.exists(session) {
+        if session.is_empty() {
+            Err(Error::Msg("session name can't be empty".to_string()))
+        } else if self.exists(session) {
             Err(Error::Msg(
                 "session with this name already exists".to_string(),
             ))
         } else {
             self.sessions
                 .insert(session.to_owned(), Session::new(scramble_type));
+            self.order.push(session.to_owned());
+            self.pending.push(Event::CreateSession {
+                session: session.to_owned(),
+                scramble_type: scramble_type.to_owned(),
+            });
             Ok(())
         }
     }
 
+    /// Renames given session
+    pub fn rename_session(
+        &mut self,
+        name: &str,
+        new: &str,
+    ) -> Result<(), Error> {
+        if new.is_empty() {
+            return Err(Error::Msg("session name can't be empty".to_string()));
+        }
+        if name != new && self.exists(new) {
+            return Err(Error::Msg(
+                "session with this name already exists".to_string(),
+            ));
+        }
+
+        let session = self
+            .sessions
+            .remove(name)
+            .ok_or(Error::Msg("non existing session".to_string()))?;
+        self.sessions.insert(new.to_owned(), session);
+        if let Some(pos) = self.order.iter().position(|s| s == name) {
+            self.order[pos] = new.to_owned();
+        }
+        self.pending.push(Event::RenameSession {
+            session: name.to_owned(),
+            name: new.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Removes given session with all its stats
+    pub fn remove_session(&mut self, name: &str) -> Result<Session, Error> {
+        let session = self
+            .sessions
+            .remove(name)
+            .ok_or(Error::Msg("non existing session".to_string()))?;
+        self.order.retain(|s| s != name);
+        self.pending.push(Event::RemoveSession {
+            session: name.to_owned(),
+        });
+        Ok(session)
+    }
+
+    /// Moves given session by the given offset in the user defined order
+    pub fn move_session(&mut self, name: &str, offset: isize) {
+        let Some(pos) = self.order.iter().position(|s| s == name) else {
+            return;
+        };
+        let new = pos
+            .saturating_add_signed(offset)
+            .min(self.order.len().saturating_sub(1));
+        let session = self.order.remove(pos);
+        self.order.insert(new, session);
+        self.pending.push(Event::Reorder);
+    }
+
+    /// Marks given session as used now
+    pub fn mark_used(&mut self, name: &str) {
+        if let Some(session) = self.sessions.get_mut(name) {
+            session.last_used = Some(Local::now());
+            self.pending.push(Event::UpdateSession {
+                session: name.to_owned(),
+            });
+        }
+    }
+
     pub fn get_session(&self, name: &str) -> Option<&Session> {
         self.sessions.get(name)
     }
 
-    /// Gets all session names
-    pub fn get_sessions(&self) -> Vec<String> {
-        self.sessions.keys().map(|v| v.to_string()).collect()
+    /// Gets mutable reference to the session with given name. Settings of
+    /// the session are saved next time the stats are saved
+    pub fn get_session_mut(&mut self, name: &str) -> Option<&mut Session> {
+        let session = self.sessions.get_mut(name)?;
+        self.pending.push(Event::UpdateSession {
+            session: name.to_owned(),
+        });
+        Some(session)
+    }
+
+    /// Gets all session names sorted by given [`SessionSort`]
+    pub fn get_sessions(&self, sort: SessionSort) -> Vec<String> {
+        let mut sessions = self.order.clone();
+        match sort {
+            SessionSort::Custom => {}
+            SessionSort::Recent => sessions.sort_by_key(|s| {
+                std::cmp::Reverse(self.sessions[s].last_used)
+            }),
+            SessionSort::Name => {
+                sessions.sort_by_key(|s| s.to_lowercase());
+            }
+        }
+        sessions
     }
 
     /// Checks whether session exists
@@ -94,31 +407,37 @@ impl Stats {
         self.sessions.contains_key(session)
     }
 
-    /// Gets average of the given session
-    pub fn avg(&self, session: &str) -> Option<Duration> {
+    /// Gets mean of the given session, DNF solves are not counted in.
+    /// The mean is DNF when all the solves are DNF
+    pub fn avg(&self, session: &str) -> Option<SolveTime> {
         let session = self.sessions.get(session)?;
-        let total: Duration = session.stats.iter().map(|s| s.time).sum();
-
         if session.stats.is_empty() {
-            None
-        } else {
-            Some(total / session.stats.len() as u32)
-        }
-    }
-
-    /// Gets average of 5 of the given session
-    pub fn avg_of(&self, session: &str, n: usize) -> Option<Duration> {
-        let session = self.sessions.get(session)?;
-        if session.stats.len() < n {
             return None;
         }
 
-        let mut times: Vec<Duration> =
-            session.stats.iter().take(n).map(|s| s.time).collect();
-        times.sort();
+        let times: Vec<_> = session
+            .stats
+            .iter()
+            .filter_map(|s| match s.solve_time() {
+                SolveTime::Time(time) => Some(time),
+                SolveTime::Dnf => None,
+            })
+            .collect();
 
-        let total: Duration = times[1..(n - 1)].iter().sum();
-        Some(total / (n - 2) as u32)
+        if times.is_empty() {
+            return Some(SolveTime::Dnf);
+        }
+        let total: Duration = times.iter().sum();
+        Some(SolveTime::Time(total / times.len() as u32))
+    }
+
+    /// Computes given [`Average`] of the latest solves in given session
+    pub fn average(
+        &self,
+        session: &str,
+        average: Average,
+    ) -> Option<SolveTime> {
+        self.sessions.get(session)?.average_at(0, average)
     }
 
     /// This might be removed (doesn't really make sense to be here)
@@ -126,8 +445,7 @@ impl Stats {
     pub fn print_sessions(&self) {
         println!("\x1b[92mSessions:");
 
-        let keys: Vec<_> = self.sessions.keys().cloned().collect();
-        for key in keys {
+        for key in self.get_sessions(SessionSort::Custom) {
             print!("  \x1b[93m{key}\x1b[0m (scramble type: ");
             match self.sessions.get(&key) {
                 Some(session) => println!("{})", session.scramble_type),
@@ -135,18 +453,115 @@ impl Stats {
             }
         }
     }
+
+    /// Applies given journal event. Applying event is idempotent, so events
+    /// already contained in the snapshot don't change anything. Session
+    /// events aren't journaled, so they're ignored
+    pub fn apply(&mut self, event: &Event) {
+        match event {
+            Event::Add { session, stat } => {
+                // Records are updated after all events are applied
+                if let Some(session) = self.sessions.get_mut(session) {
+                    session.insert(stat.clone());
+                }
+            }
+            Event::Remove { session, id } => {
+                if let Some(session) = self.sessions.get_mut(session) {
+                    if let Some(index) = session.index_of(*id) {
+                        session.remove(index);
+                    }
+                }
+            }
+            Event::Penalty {
+                session,
+                id,
+                penalty,
+            } => {
+                if let Some(stat) = self.stat_mut(session, *id) {
+                    stat.penalty = *penalty;
+                }
+            }
+            Event::Comment {
+                session,
+                id,
+                comment,
+            } => {
+                if let Some(stat) = self.stat_mut(session, *id) {
+                    stat.set_comment(comment.clone());
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 impl Stats {
-    /// Gets the directory to save stats in
-    fn get_stats_dir() -> Result<String, Error> {
-        let config = config_dir()
-            .ok_or(Error::Msg("Can't get stats directory".to_string()))?;
-
-        Ok(config
-            .to_str()
-            .ok_or(Error::Msg("Invalid path to stats".to_string()))?
-            .to_owned()
-            + "/rstimer/stats")
+    /// Gets mutable reference to the stat with given id in given session
+    fn stat_mut(&mut self, session: &str, id: SolveId) -> Option<&mut Stat> {
+        let session = self.sessions.get_mut(session)?;
+        let index = session.index_of(id)?;
+        session.stats.get_mut(index)
+    }
+
+    /// Makes sure the user defined order contains each session exactly once.
+    /// Sessions missing in the order (for example from stats files created
+    /// before the order was saved) are appended by the date of their first
+    /// solve
+    fn normalize_order(&mut self) {
+        let mut seen = HashSet::new();
+        self.order.retain(|s| {
+            self.sessions.contains_key(s) && seen.insert(s.clone())
+        });
+
+        let mut missing: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|(name, _)| !seen.contains(*name))
+            .map(|(name, session)| {
+                (session.stats.last().map(|s| s.datetime), name.clone())
+            })
+            .collect();
+        missing.sort();
+        self.order.extend(missing.into_iter().map(|(_, name)| name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Local, TimeZone};
+
+    use super::Stats;
+    use crate::stats::stat::{Penalty, Stat};
+
+    /// Gets solves set in the same second, like csTimer exports them
+    fn solves() -> Vec<Stat> {
+        let datetime = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+        [(9_870, "R U F"), (10_120, "D L B")]
+            .map(|(millis, scramble)| {
+                let mut stat = Stat::new(
+                    Duration::from_millis(millis),
+                    Penalty::None,
+                    scramble.to_owned(),
+                    String::new(),
+                );
+                stat.datetime = datetime;
+                stat
+            })
+            .to_vec()
+    }
+
+    #[test]
+    fn import_same_second() {
+        let mut stats = Stats::default();
+        stats.add_session("3x3", "3x3x3").unwrap();
+
+        assert_eq!(stats.import("3x3", solves()).unwrap(), 2);
+        assert_eq!(stats.sessions["3x3"].stats.len(), 2);
+
+        // Importing the same file again adds nothing
+        assert_eq!(stats.import("3x3", solves()).unwrap(), 0);
+        assert_eq!(stats.sessions["3x3"].stats.len(), 2);
     }
 }
@@ -0,0 +1,89 @@
+use std::{
+    fs::{copy, metadata, read, remove_file, rename},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{error::Error, storage::with_suffix};
+
+/// Minimal age of the newest backup before a new one is created
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Backup of a file
+#[derive(Debug)]
+pub struct Backup {
+    pub index: usize,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+}
+
+/// Gets path of the backup with given index (`1` is the newest)
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    with_suffix(path, &format!(".{index}"))
+}
+
+/// Creates new backup of the given file, when the newest backup is older
+/// than the backup interval
+///
+/// **Parameters:**
+/// * `path` - path of the file to back up
+/// * `count` - number of backups to keep
+pub fn backup(path: &Path, count: usize) -> Result<(), Error> {
+    let newest = backup_path(path, 1);
+    let recent = metadata(&newest)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|m| m.elapsed().ok())
+        .is_some_and(|age| age < BACKUP_INTERVAL);
+    if recent {
+        return Ok(());
+    }
+    rotate(path, count)
+}
+
+/// Rotates backups of the given file - the oldest one is removed, others
+/// are shifted and the file is copied to the newest backup
+///
+/// **Parameters:**
+/// * `path` - path of the file to back up
+/// * `count` - number of backups to keep
+pub fn rotate(path: &Path, count: usize) -> Result<(), Error> {
+    if count == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    let oldest = backup_path(path, count);
+    if oldest.exists() {
+        remove_file(oldest)?;
+    }
+    for i in (1..count).rev() {
+        let from = backup_path(path, i);
+        if from.exists() {
+            rename(from, backup_path(path, i + 1))?;
+        }
+    }
+    copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Lists all existing backups of the given file, newest first
+pub fn list(path: &Path) -> Vec<Backup> {
+    (1..)
+        .map(|index| (index, backup_path(path, index)))
+        .take_while(|(_, path)| path.exists())
+        .map(|(index, path)| Backup {
+            index,
+            modified: metadata(&path).and_then(|m| m.modified()).ok(),
+            path,
+        })
+        .collect()
+}
+
+/// Reads backup with given index
+pub fn read_backup(path: &Path, index: usize) -> Result<Vec<u8>, Error> {
+    let backup = backup_path(path, index);
+    if !backup.exists() {
+        return Err(Error::Msg(format!("backup {index} doesn't exist")));
+    }
+    Ok(read(backup)?)
+}
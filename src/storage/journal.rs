@@ -0,0 +1,82 @@
+use std::{
+    fs::{remove_file, File, OpenOptions},
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::{error::Error, stats::event::Event};
+
+/// Events read from the journal
+#[derive(Debug, Default)]
+pub struct Journal {
+    pub events: Vec<Event>,
+    /// Whether the journal contains line which couldn't be parsed (for
+    /// example when write was interrupted)
+    pub damaged: bool,
+    /// Offset in bytes where the read ended
+    pub end: u64,
+}
+
+/// Reads events from the journal on given path
+///
+/// **Parameters:**
+/// * `path` - path of the journal
+/// * `offset` - offset in bytes to start reading from, events before it
+///   were already read
+pub fn read(path: &Path, offset: u64) -> Result<Journal, Error> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(Journal::default())
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // Journal shorter than the offset was rewritten, so it's read whole
+    let offset = if file.metadata()?.len() < offset {
+        0
+    } else {
+        offset
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+
+    let mut journal = Journal {
+        end: offset + text.len() as u64,
+        ..Default::default()
+    };
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(event) => journal.events.push(event),
+            Err(_) => journal.damaged = true,
+        }
+    }
+    Ok(journal)
+}
+
+/// Appends given events to the journal on given path
+///
+/// **Returns:**
+/// * Length of the journal in bytes after the append
+pub fn append(path: &Path, events: &[Event]) -> Result<u64, Error> {
+    let mut text = String::new();
+    for event in events {
+        text.push_str(&serde_json::to_string(event)?);
+        text.push('\n');
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_data()?;
+    Ok(file.metadata()?.len())
+}
+
+/// Removes the journal on given path, used after it's compacted into the
+/// snapshot
+pub fn clear(path: &Path) -> Result<(), Error> {
+    match remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    fs::{metadata, read_to_string, rename},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::Local;
+
+use crate::{
+    error::Error,
+    stats::{event::Event, stats::Stats},
+    storage::{
+        backups, journal, lock,
+        migrate::{self, Format},
+        with_suffix, write_atomic, Storage,
+    },
+};
+
+/// Number of journal events after which it's compacted into the snapshot
+const COMPACT_THRESHOLD: usize = 1000;
+
+/// Stores stats in the JSON snapshot file. Solve changes are appended to
+/// the journal next to it, which is compacted into the snapshot when it gets
+/// long or when sessions change
+#[derive(Debug)]
+pub struct JsonStorage {
+    path: PathBuf,
+    /// Number of events in the journal
+    journal_len: usize,
+    /// Offset in bytes of the journal up to which it was read
+    journal_end: u64,
+    /// Size and modification time of the snapshot when it was last read
+    /// or written, used to detect writes of other instances
+    snapshot: Option<(u64, SystemTime)>,
+    /// Whether the journal should be compacted into the snapshot on save
+    compact: bool,
+    /// Number of stats backups to keep
+    backups: usize,
+}
+
+impl JsonStorage {
+    /// Creates new JSON storage of the snapshot on given path
+    ///
+    /// **Parameters:**
+    /// * `path` - path of the snapshot
+    /// * `backups` - number of stats backups to keep
+    pub fn new(path: PathBuf, backups: usize) -> Self {
+        Self {
+            path,
+            backups,
+            journal_len: 0,
+            journal_end: 0,
+            snapshot: None,
+            compact: false,
+        }
+    }
+
+    /// Reads the snapshot and replays the journal, the stats file has to be
+    /// locked
+    fn read(&mut self) -> Result<Stats, Error> {
+        self.snapshot = file_id(&self.path);
+        let mut stats = match read_to_string(&self.path) {
+            Ok(text) => match JsonStorage::parse(text.as_bytes()) {
+                Ok(stats) => stats,
+                Err(Error::Serde(e)) => return Err(self.backup_corrupt(e)),
+                Err(e) => return Err(e),
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound => Stats::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let journal = journal::read(&self.journal_path(), 0)?;
+        journal.events.iter().for_each(|e| stats.apply(e));
+        self.journal_len = journal.events.len();
+        self.journal_end = journal.end;
+        self.compact = journal.damaged;
+        Ok(stats)
+    }
+
+    /// Merges changes saved by other instances since the last read or
+    /// write into given stats, the stats file has to be locked
+    fn merge(
+        &mut self,
+        stats: &mut Stats,
+        events: &[Event],
+    ) -> Result<(), Error> {
+        if file_id(&self.path) != self.snapshot {
+            let current = self.read()?;
+            stats.merge(current, events);
+            return Ok(());
+        }
+
+        let journal = journal::read(&self.journal_path(), self.journal_end)?;
+        if !journal.events.is_empty() {
+            stats.merge_events(&journal.events);
+        }
+        self.journal_len += journal.events.len();
+        self.journal_end = journal.end;
+        self.compact |= journal.damaged;
+        Ok(())
+    }
+
+    /// Parses the stats snapshot, migrating it from older versions of the
+    /// format
+    fn parse(text: &[u8]) -> Result<Stats, Error> {
+        let mut value = serde_json::from_slice(text)?;
+        migrate::migrate(&mut value, Format::Stats)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Backs up the snapshot, writes whole stats snapshot and clears the
+    /// journal
+    fn write_snapshot(&mut self, stats: &Stats) -> Result<(), Error> {
+        backups::backup(&self.path, self.backups)?;
+        self.write_stats(stats)
+    }
+
+    /// Writes whole stats snapshot and clears the journal, without backing
+    /// up the snapshot
+    fn write_stats(&mut self, stats: &Stats) -> Result<(), Error> {
+        let mut value = serde_json::to_value(stats)?;
+        migrate::set_version(&mut value, Format::Stats);
+        let text = serde_json::to_string_pretty(&value)?;
+        write_atomic(&self.path, text.as_bytes())?;
+
+        journal::clear(&self.journal_path())?;
+        self.snapshot = file_id(&self.path);
+        self.journal_len = 0;
+        self.journal_end = 0;
+        self.compact = false;
+        Ok(())
+    }
+
+    /// Gets path of the journal
+    fn journal_path(&self) -> PathBuf {
+        with_suffix(&self.path, ".journal")
+    }
+
+    /// Gets path of the lock file, which is locked while stats are read or
+    /// written
+    fn lock_path(&self) -> PathBuf {
+        with_suffix(&self.path, ".lock")
+    }
+
+    /// Moves corrupt stats file aside to a timestamped backup. The journal
+    /// is moved next to the backup, so that its solves aren't lost when
+    /// the stats are saved again
+    ///
+    /// **Returns:**
+    /// * Error describing what happened
+    fn backup_corrupt(&self, err: serde_json::Error) -> Error {
+        let base = with_suffix(
+            &self.path,
+            &format!(".corrupt-{}", Local::now().format("%Y%m%d-%H%M%S")),
+        );
+        let mut backup = base.clone();
+        let mut i = 1;
+        while backup.exists() {
+            backup = with_suffix(&base, &format!("-{i}"));
+            i += 1;
+        }
+
+        // Journal is moved first, so it's never replayed without its snapshot
+        let journal = self.journal_path();
+        if journal.exists() {
+            if let Err(e) = rename(&journal, with_suffix(&backup, ".journal"))
+            {
+                return Error::Msg(format!(
+                    "stats file is corrupt ({err}) and its journal couldn't \
+                    be moved aside ({e})"
+                ));
+            }
+        }
+        match rename(&self.path, &backup) {
+            Ok(_) => Error::CorruptStats(backup, err),
+            Err(e) => Error::Msg(format!(
+                "stats file is corrupt ({err}) and it couldn't be moved \
+                aside ({e})"
+            )),
+        }
+    }
+}
+
+impl Storage for JsonStorage {
+    /// Loads stats from the snapshot JSON file and replays the journal of
+    /// solve events on top of it. When the snapshot can't be parsed, it's
+    /// moved aside to a timestamped backup and error is returned, so that it
+    /// doesn't get overwritten
+    fn load(&mut self) -> Result<Stats, Error> {
+        let _lock = lock(&self.lock_path())?;
+        self.read()
+    }
+
+    /// Appends solve changes to the journal, whole snapshot is written only
+    /// when sessions changed or when the journal gets too long
+    fn save(
+        &mut self,
+        stats: &mut Stats,
+        events: &[Event],
+    ) -> Result<(), Error> {
+        let _lock = lock(&self.lock_path())?;
+        self.merge(stats, events)?;
+
+        if self.compact
+            || !self.path.exists()
+            || events.iter().any(|e| !e.is_solve())
+            || self.journal_len + events.len() > COMPACT_THRESHOLD
+        {
+            return self.write_snapshot(stats);
+        }
+
+        if !events.is_empty() {
+            self.journal_end = journal::append(&self.journal_path(), events)?;
+            self.journal_len += events.len();
+        }
+        Ok(())
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn restore(&mut self, index: usize) -> Result<(), Error> {
+        let text = backups::read_backup(&self.path, index)?;
+        JsonStorage::parse(&text)?;
+
+        let _lock = lock(&self.lock_path())?;
+        // Current stats are backed up once, including the journal
+        if let Ok(current) = self.read() {
+            self.write_stats(&current)?;
+        }
+        backups::rotate(&self.path, self.backups.max(1))?;
+        write_atomic(&self.path, &text)?;
+        journal::clear(&self.journal_path())
+    }
+}
+
+/// Gets size and modification time of the file on given path
+fn file_id(path: &Path) -> Option<(u64, SystemTime)> {
+    let meta = metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{create_dir_all, read_to_string, remove_dir_all, write, File},
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+
+    use super::JsonStorage;
+    use crate::{
+        error::Error,
+        stats::{
+            event::Event,
+            stat::{Penalty, Stat},
+            stats::Stats,
+        },
+        storage::{backups::backup_path, journal, with_suffix, Storage},
+    };
+
+    /// Creates empty directory for the test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("rstimer-{}-json-{name}", std::process::id()));
+        _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Gets stats file with one empty session of given name
+    fn stats_file(session: &str) -> String {
+        format!(
+            r#"{{"version":2,"sessions":{{"{session}":
+            {{"scramble_type":"3x3x3","stats":[]}}}}}}"#
+        )
+    }
+
+    #[test]
+    fn restore_uses_one_backup() {
+        let dir = temp_dir("restore");
+        let path = dir.join("stats");
+        write(&path, stats_file("current")).unwrap();
+        write(backup_path(&path, 1), stats_file("first")).unwrap();
+        write(backup_path(&path, 2), stats_file("second")).unwrap();
+        // Newest backup is old enough for the regular backup
+        let old = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(backup_path(&path, 1))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let mut storage = JsonStorage::new(path.clone(), 3);
+        storage.restore(2).unwrap();
+
+        let backup = |i| read_to_string(backup_path(&path, i)).unwrap();
+        assert!(read_to_string(&path).unwrap().contains("second"));
+        assert!(backup(1).contains("current"));
+        assert!(backup(2).contains("first"));
+        assert!(backup(3).contains("second"));
+        assert!(!backup_path(&path, 4).exists());
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn corrupt_keeps_journal() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("stats");
+        write(&path, "{\"sessions\":").unwrap();
+        let stat = Stat::new(
+            Duration::from_millis(12_340),
+            Penalty::None,
+            "R U".to_owned(),
+            String::new(),
+        );
+        let add = Event::Add {
+            session: "3x3".to_owned(),
+            stat: stat.clone(),
+        };
+        journal::append(&with_suffix(&path, ".journal"), &[add]).unwrap();
+
+        let mut storage = JsonStorage::new(path.clone(), 3);
+        let Err(Error::CorruptStats(backup, _)) = storage.load() else {
+            panic!("corrupt stats loaded");
+        };
+        // User starts with empty stats and creates session
+        let mut stats = Stats::default();
+        let create = Event::CreateSession {
+            session: "2x2".to_owned(),
+            scramble_type: "2x2x2".to_owned(),
+        };
+        storage.save(&mut stats, &[create]).unwrap();
+
+        let journal =
+            journal::read(&with_suffix(&backup, ".journal"), 0).unwrap();
+        assert!(journal.events.iter().any(|e| matches!(
+            e,
+            Event::Add { stat: s, .. } if s.id == stat.id
+        )));
+        _ = remove_dir_all(dir);
+    }
+
+    /// Applies the event to the stats and saves it
+    fn save(storage: &mut JsonStorage, stats: &mut Stats, event: Event) {
+        stats.apply(&event);
+        storage.save(stats, &[event]).unwrap();
+    }
+
+    /// Creates session with given name and saves it
+    fn create(storage: &mut JsonStorage, stats: &mut Stats, session: &str) {
+        stats.add_session(session, "3x3x3").unwrap();
+        let event = Event::CreateSession {
+            session: session.to_owned(),
+            scramble_type: "3x3x3".to_owned(),
+        };
+        storage.save(stats, &[event]).unwrap();
+    }
+
+    /// Gets event adding solve with given time to the 3x3 session
+    fn add(millis: u64) -> Event {
+        Event::Add {
+            session: "3x3".to_owned(),
+            stat: Stat::new(
+                Duration::from_millis(millis),
+                Penalty::None,
+                "R U".to_owned(),
+                String::new(),
+            ),
+        }
+    }
+
+    #[test]
+    fn merge_instances() {
+        let dir = temp_dir("merge");
+        let path = dir.join("stats");
+        let mut first = JsonStorage::new(path.clone(), 3);
+        let mut first_stats = first.load().unwrap();
+        create(&mut first, &mut first_stats, "3x3");
+
+        let mut second = JsonStorage::new(path.clone(), 3);
+        let mut second_stats = second.load().unwrap();
+        save(&mut first, &mut first_stats, add(10_000));
+        save(&mut second, &mut second_stats, add(11_000));
+        save(&mut first, &mut first_stats, add(12_000));
+        // Creating session compacts the journal into the snapshot
+        create(&mut second, &mut second_stats, "2x2");
+        save(&mut first, &mut first_stats, add(13_000));
+        save(&mut second, &mut second_stats, add(14_000));
+
+        let stats = JsonStorage::new(path, 3).load().unwrap();
+        let mut times: Vec<_> = stats.sessions["3x3"]
+            .stats
+            .iter()
+            .map(|s| s.time.as_millis())
+            .collect();
+        times.sort();
+        assert_eq!(times, [10_000, 11_000, 12_000, 13_000, 14_000]);
+        assert!(stats.exists("2x2"));
+        // Instance which saved last has all the solves too
+        assert_eq!(second_stats.sessions["3x3"].stats.len(), times.len());
+        _ = remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,441 @@
+use std::{
+    collections::HashSet, fs::read_to_string, io::ErrorKind, path::Path,
+};
+
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::Error,
+    stats::stat::Stat,
+    storage::{backups, write_atomic},
+};
+
+/// Migration of the file to the next version, returns description of the
+/// changes it made
+type Migration = fn(&mut Map<String, Value>) -> Vec<String>;
+
+/// Migrations of the stats file, migration on index `i` upgrades the file
+/// from version `i` to `i + 1`
+const STATS: &[Migration] = &[stats_v1, stats_v2];
+
+/// Migrations of the config file, migration on index `i` upgrades the file
+/// from version `i` to `i + 1`
+const CONFIG: &[Migration] = &[config_v1];
+
+/// Format of the versioned file
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Stats,
+    Config,
+}
+
+impl Format {
+    /// Gets current version of the format
+    pub fn version(&self) -> u64 {
+        self.migrations().len() as u64
+    }
+
+    /// Gets name of the format
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Stats => "stats",
+            Format::Config => "config",
+        }
+    }
+
+    fn migrations(&self) -> &'static [Migration] {
+        match self {
+            Format::Stats => STATS,
+            Format::Config => CONFIG,
+        }
+    }
+}
+
+/// Report of the migration
+#[derive(Debug, Default)]
+pub struct Report {
+    pub from: u64,
+    pub to: u64,
+    pub changes: Vec<String>,
+}
+
+/// Migrates given file contents to the current version of the format.
+/// Files without version are version `0`
+///
+/// **Returns:**
+/// * Report of the migration, error when the file is newer than supported
+pub fn migrate(value: &mut Value, format: Format) -> Result<Report, Error> {
+    // Invalid format is reported when deserializing
+    let Some(map) = value.as_object_mut() else {
+        return Ok(Report::default());
+    };
+
+    let from = match map.get("version") {
+        Some(version) => version.as_u64().ok_or(Error::Msg(format!(
+            "invalid {} version: {version}",
+            format.name()
+        )))?,
+        None => 0,
+    };
+    let to = format.version();
+    if from > to {
+        return Err(Error::Msg(format!(
+            "{} file has version {from}, but this version of rsTimer \
+            supports only versions up to {to}, please update rsTimer",
+            format.name()
+        )));
+    }
+
+    let changes = format.migrations()[from as usize..]
+        .iter()
+        .flat_map(|migration| migration(map))
+        .collect();
+    map.insert("version".to_string(), to.into());
+    Ok(Report { from, to, changes })
+}
+
+/// Sets version of given file contents to the current version of the format
+pub fn set_version(value: &mut Value, format: Format) {
+    if let Some(map) = value.as_object_mut() {
+        map.insert("version".to_string(), format.version().into());
+    }
+}
+
+/// Migrates file on given path. The file is backed up before it's
+/// overwritten
+///
+/// **Parameters:**
+/// * `path` - path of the file to migrate
+/// * `format` - format of the file
+/// * `dry_run` - when true, the file is not changed
+/// * `backups` - number of backups to keep
+///
+/// **Returns:**
+/// * Report of the migration, [`None`] when the file doesn't exist
+pub fn migrate_file(
+    path: &Path,
+    format: Format,
+    dry_run: bool,
+    backups: usize,
+) -> Result<Option<Report>, Error> {
+    let text = match read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut value = serde_json::from_str(&text)?;
+    let report = migrate(&mut value, format)?;
+    if !dry_run && report.from != report.to {
+        backups::rotate(path, backups.max(1))?;
+        let text = serde_json::to_string_pretty(&value)?;
+        write_atomic(path, text.as_bytes())?;
+    }
+    Ok(Some(report))
+}
+
+/// Version 1 - adds penalties, session settings and session order, which
+/// were added to the format before it was versioned
+fn stats_v1(stats: &mut Map<String, Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if !stats.contains_key("order") {
+        stats.insert("order".to_string(), json!([]));
+        changes.push("added session order".to_string());
+    }
+
+    let (mut sessions, mut solves) = (0, 0);
+    let values = stats.get_mut("sessions").and_then(Value::as_object_mut);
+    for session in values.into_iter().flat_map(|s| s.values_mut()) {
+        let Some(session) = session.as_object_mut() else {
+            continue;
+        };
+
+        let defaults = [
+            ("inspection", json!(false)),
+            ("averages", json!(["ao5", "ao12"])),
+            ("last_used", Value::Null),
+        ];
+        let mut changed = false;
+        for (key, default) in defaults {
+            if !session.contains_key(key) {
+                session.insert(key.to_string(), default);
+                changed = true;
+            }
+        }
+        sessions += changed as usize;
+
+        let values = session.get_mut("stats").and_then(Value::as_array_mut);
+        for stat in values.into_iter().flatten() {
+            if let Some(stat) = stat.as_object_mut() {
+                if !stat.contains_key("penalty") {
+                    stat.insert("penalty".to_string(), json!("None"));
+                    solves += 1;
+                }
+            }
+        }
+    }
+
+    if sessions > 0 {
+        changes.push(format!("added settings to {sessions} sessions"));
+    }
+    if solves > 0 {
+        changes.push(format!("added penalty to {solves} solves"));
+    }
+    changes
+}
+
+/// Version 2 - adds unique id to each solve, solves were identified by
+/// their date and time before. Ids are derived from the date and time, so
+/// the journal written before the migration still applies
+fn stats_v2(stats: &mut Map<String, Value>) -> Vec<String> {
+    let mut solves = 0;
+    let values = stats.get_mut("sessions").and_then(Value::as_object_mut);
+    for session in values.into_iter().flat_map(|s| s.values_mut()) {
+        let values = session.get_mut("stats").and_then(Value::as_array_mut);
+        let mut used = HashSet::new();
+        for stat in values.into_iter().flatten() {
+            let Some(stat) = stat.as_object_mut() else {
+                continue;
+            };
+            let Some(datetime) = stat
+                .get("datetime")
+                .and_then(|d| DateTime::<Local>::deserialize(d).ok())
+            else {
+                continue;
+            };
+
+            let mut id = Stat::legacy_id(&datetime);
+            while !used.insert(id) {
+                id += 1;
+            }
+            stat.insert("id".to_string(), id.into());
+            solves += 1;
+        }
+    }
+
+    if solves == 0 {
+        return Vec::new();
+    }
+    vec![format!("added id to {solves} solves")]
+}
+
+/// Version 1 - adds session sort, number of backups and storage backend,
+/// which were added to the format before it was versioned
+fn config_v1(config: &mut Map<String, Value>) -> Vec<String> {
+    let defaults = [
+        ("session_sort", json!("Custom")),
+        ("backups", json!(5)),
+        ("storage", json!("json")),
+    ];
+    let mut changes = Vec::new();
+    for (key, default) in defaults {
+        if !config.contains_key(key) {
+            changes.push(format!("added {key} ({default})"));
+            config.insert(key.to_string(), default);
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{create_dir_all, read_to_string, remove_dir_all, write},
+        path::PathBuf,
+        time::Duration,
+    };
+
+    use serde_json::Value;
+
+    use super::{migrate, migrate_file, Format};
+    use crate::{
+        asci::digit_type::DigitType,
+        config::Config,
+        stats::{
+            session_sort::SessionSort,
+            stat::{SolveTime, Stat},
+            stats::Stats,
+        },
+        storage::{with_suffix, Backend},
+    };
+
+    const STATS_V0: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/stats_v0.json"
+    ));
+    const STATS_V1: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/stats_v1.json"
+    ));
+    const STATS_V2: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/stats_v2.json"
+    ));
+    const CONFIG_V0: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/config_v0.json"
+    ));
+    const CONFIG_V1: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/config_v1.json"
+    ));
+
+    /// Migrates the fixture and checks it ends up in the current version
+    fn migrate_fixture(text: &str, format: Format, from: u64) -> Value {
+        let mut value = serde_json::from_str(text).unwrap();
+        let report = migrate(&mut value, format).unwrap();
+        assert_eq!(report.from, from);
+        assert_eq!(report.to, format.version());
+        assert_eq!(value["version"], format.version());
+        value
+    }
+
+    fn times(stats: &Stats, session: &str) -> Vec<SolveTime> {
+        stats.sessions[session]
+            .stats
+            .iter()
+            .map(Stat::solve_time)
+            .collect()
+    }
+
+    fn ids(stats: &Stats, session: &str) -> Vec<u64> {
+        stats.sessions[session].stats.iter().map(|s| s.id).collect()
+    }
+
+    /// Creates empty temporary directory unique for the test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("rstimer-{}-{name}", std::process::id()));
+        _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn stats_v0() {
+        let value = migrate_fixture(STATS_V0, Format::Stats, 0);
+        let mut stats: Stats = serde_json::from_value(value).unwrap();
+        stats.prepare();
+
+        let mut sessions = stats.get_sessions(SessionSort::Custom);
+        sessions.sort();
+        assert_eq!(sessions, ["2x2", "3x3"]);
+        let session = &stats.sessions["3x3"];
+        assert!(!session.inspection);
+        assert_eq!(session.averages.len(), 2);
+        assert_eq!(
+            times(&stats, "3x3"),
+            [
+                SolveTime::Time(Duration::from_millis(12345)),
+                SolveTime::Time(Duration::from_millis(10980)),
+                SolveTime::Time(Duration::from_millis(11500)),
+            ]
+        );
+        assert_eq!(session.stats[1].comment(), "PLL skip");
+
+        // Solves set at the same time get distinct ids
+        let ids = ids(&stats, "3x3");
+        assert!(ids.iter().all(|id| *id != 0));
+        assert_eq!(ids[2], ids[1] + 1);
+    }
+
+    #[test]
+    fn stats_v1() {
+        let value = migrate_fixture(STATS_V1, Format::Stats, 1);
+        let stats: Stats = serde_json::from_value(value).unwrap();
+
+        assert_eq!(stats.get_sessions(SessionSort::Custom), ["2x2", "3x3"]);
+        assert!(stats.sessions["3x3"].inspection);
+        assert_eq!(
+            times(&stats, "3x3"),
+            [
+                SolveTime::Time(Duration::from_millis(14345)),
+                SolveTime::Dnf,
+            ]
+        );
+        assert!(ids(&stats, "3x3").iter().all(|id| *id != 0));
+    }
+
+    #[test]
+    fn stats_v2() {
+        let value = migrate_fixture(STATS_V2, Format::Stats, 2);
+        let stats: Stats = serde_json::from_value(value).unwrap();
+        assert_eq!(ids(&stats, "3x3"), [1 << 62, 17]);
+    }
+
+    #[test]
+    fn migration_ids_are_stable() {
+        let a = migrate_fixture(STATS_V0, Format::Stats, 0);
+        let b = migrate_fixture(STATS_V0, Format::Stats, 0);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn config_v0() {
+        let value = migrate_fixture(CONFIG_V0, Format::Config, 0);
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.font, DigitType::Train);
+        assert_eq!(config.session_sort, SessionSort::Custom);
+        assert_eq!(config.backups, 5);
+        assert_eq!(config.storage, Backend::Json);
+    }
+
+    #[test]
+    fn config_v1() {
+        let value = migrate_fixture(CONFIG_V1, Format::Config, 1);
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.font, DigitType::Card);
+        assert_eq!(config.session_sort, SessionSort::Recent);
+        assert_eq!(config.backups, 3);
+    }
+
+    #[test]
+    fn newer_version_fails() {
+        let mut value = serde_json::json!({ "version": 1000, "font": "Card" });
+        assert!(migrate(&mut value, Format::Config).is_err());
+    }
+
+    #[test]
+    fn dry_run_doesnt_write() {
+        let dir = temp_dir("dry-run");
+        for (name, text, format) in [
+            ("stats", STATS_V0, Format::Stats),
+            ("config.json", CONFIG_V0, Format::Config),
+        ] {
+            let path = dir.join(name);
+            write(&path, text).unwrap();
+
+            let report = migrate_file(&path, format, true, 5).unwrap();
+            let report = report.unwrap();
+            assert_eq!((report.from, report.to), (0, format.version()));
+            assert!(!report.changes.is_empty());
+            assert_eq!(read_to_string(&path).unwrap(), text);
+            assert!(!with_suffix(&path, ".1").exists());
+        }
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn migrate_writes_with_backup() {
+        let dir = temp_dir("migrate");
+        let path = dir.join("stats");
+        write(&path, STATS_V0).unwrap();
+
+        let report = migrate_file(&path, Format::Stats, false, 5).unwrap();
+        assert!(report.is_some_and(|r| r.from == 0));
+        let value: Value =
+            serde_json::from_str(&read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["version"], Format::Stats.version());
+        assert_eq!(
+            read_to_string(with_suffix(&path, ".1")).unwrap(),
+            STATS_V0
+        );
+
+        // Migrated file is up to date
+        let report = migrate_file(&path, Format::Stats, false, 5).unwrap();
+        assert!(report.is_some_and(|r| r.from == r.to));
+        _ = remove_dir_all(dir);
+    }
+}
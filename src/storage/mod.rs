@@ -0,0 +1,143 @@
+use std::{
+    ffi::OsString,
+    fmt::Debug,
+    fs::{create_dir_all, rename, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    error::Error,
+    paths::data_dir,
+    stats::{event::Event, stats::Stats},
+};
+
+pub mod backups;
+pub mod journal;
+pub mod json;
+pub mod migrate;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Storage backend of the stats
+pub trait Storage: Debug {
+    /// Loads all stats from the storage
+    fn load(&mut self) -> Result<Stats, Error>;
+
+    /// Saves given changes of the stats. Changes saved by other instances
+    /// since the last load or save are merged into the stats first
+    ///
+    /// **Parameters:**
+    /// * `stats` - current stats
+    /// * `events` - changes made since the last save
+    fn save(
+        &mut self,
+        stats: &mut Stats,
+        events: &[Event],
+    ) -> Result<(), Error>;
+
+    /// Gets path of the file the stats are stored in
+    fn path(&self) -> &Path;
+
+    /// Restores stats from the backup with given index. Current stats are
+    /// backed up first, so the restore can be reverted
+    fn restore(&mut self, index: usize) -> Result<(), Error>;
+}
+
+/// Kind of the storage backend, set in the config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// JSON snapshot with append-only journal
+    #[default]
+    Json,
+    /// SQLite database, only available with the `sqlite` feature
+    Sqlite,
+}
+
+/// Opens storage of the backend set in given config
+pub fn open(config: &Config) -> Result<Box<dyn Storage>, Error> {
+    match config.storage {
+        Backend::Json => Ok(Box::new(json::JsonStorage::new(
+            stats_path()?,
+            config.backups,
+        ))),
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite => Ok(Box::new(sqlite::SqliteStorage::open(
+            data_dir()?.join("stats.db"),
+            &stats_path()?,
+            config.backups,
+        )?)),
+        #[cfg(not(feature = "sqlite"))]
+        Backend::Sqlite => Err(Error::Msg(
+            "rsTimer was built without SQLite support, rebuild it with \
+            `--features sqlite` or change `storage` in the config"
+                .to_string(),
+        )),
+    }
+}
+
+/// Locks the file on given path exclusively, waits until other instances
+/// release it. The lock is released when the returned file is dropped
+pub fn lock(path: &Path) -> Result<File, Error> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    file.lock_exclusive()?;
+    Ok(file)
+}
+
+/// Tries to take the lock held while **rsTimer** is running
+///
+/// **Returns:**
+/// * The lock, [`None`] when another instance is running
+pub fn instance_lock() -> Result<Option<File>, Error> {
+    let path = data_dir()?.join("instance.lock");
+    create_dir_all(data_dir()?)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    Ok(file.try_lock_exclusive()?.then_some(file))
+}
+
+/// Gets path of the JSON stats file
+pub fn stats_path() -> Result<PathBuf, Error> {
+    Ok(data_dir()?.join("stats"))
+}
+
+/// Writes given contents to the file atomically. Contents are written to a
+/// temporary file first, which is then renamed to the given path, so the file
+/// is never left truncated
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let tmp = with_suffix(path, ".tmp");
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    rename(&tmp, path)?;
+
+    // Makes sure the rename itself is persisted
+    if let Some(dir) = path.parent() {
+        _ = File::open(dir).and_then(|d| d.sync_all());
+    }
+    Ok(())
+}
+
+/// Gets given path with the suffix appended to the file name
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
@@ -0,0 +1,759 @@
+use std::{
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use rusqlite::{
+    params, types::Value as SqlValue, Connection, OptionalExtension,
+    Transaction, TransactionBehavior,
+};
+
+use crate::{
+    error::Error,
+    stats::{
+        event::{Event, SolveId},
+        session::Session,
+        session_sort::SessionSort,
+        stat::{Penalty, Stat},
+        stats::Stats,
+    },
+    storage::{backups, json::JsonStorage, write_atomic, Storage},
+};
+
+/// Version of the database schema, stored in the `user_version` pragma
+const VERSION: i32 = 2;
+
+/// How long to wait for other instance to finish writing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    position INTEGER NOT NULL,
+    scramble_type TEXT NOT NULL,
+    inspection INTEGER NOT NULL,
+    averages TEXT NOT NULL,
+    last_used TEXT
+);
+CREATE TABLE solves (
+    id INTEGER PRIMARY KEY,
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    stat_id INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    penalty TEXT NOT NULL,
+    scramble TEXT NOT NULL,
+    comment TEXT NOT NULL
+);
+CREATE UNIQUE INDEX solves_session_stat ON solves(session_id, stat_id);
+CREATE INDEX solves_session_datetime ON solves(session_id, datetime, id);
+";
+
+/// Upgrades schema from version 1, where solves were identified by the date
+/// and time. Row ids are unique, so they're used as ids of existing solves
+const UPGRADE_V2: &str = "
+ALTER TABLE solves ADD COLUMN stat_id INTEGER NOT NULL DEFAULT 0;
+UPDATE solves SET stat_id = id;
+DROP INDEX solves_session_datetime;
+CREATE UNIQUE INDEX solves_session_stat ON solves(session_id, stat_id);
+CREATE INDEX solves_session_datetime ON solves(session_id, datetime, id);
+";
+
+/// Stores stats in the SQLite database. Each change is applied as a single
+/// row change, so saving doesn't depend on the number of solves
+#[derive(Debug)]
+pub struct SqliteStorage {
+    path: PathBuf,
+    conn: Connection,
+    /// Data version when the stats were last read or written, it changes
+    /// when other instance writes to the database
+    data_version: i64,
+    /// Number of database backups to keep
+    backups: usize,
+}
+
+impl SqliteStorage {
+    /// Opens the database on given path, creates it when it doesn't exist.
+    /// New database is filled with the stats from the JSON storage
+    ///
+    /// **Parameters:**
+    /// * `path` - path of the database
+    /// * `json` - path of the JSON stats to migrate from
+    /// * `backups` - number of database backups to keep
+    pub fn open(
+        path: PathBuf,
+        json: &Path,
+        backups: usize,
+    ) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        let conn = Connection::open(&path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let mut storage = Self {
+            path,
+            conn,
+            data_version: 0,
+            backups,
+        };
+        storage.init(json)?;
+        Ok(storage)
+    }
+
+    /// Creates the schema and migrates the JSON stats, when the database is
+    /// new, or upgrades the schema of older database. Both is done in one
+    /// transaction, so the migration is either done completely or not at all.
+    /// Database of newer version is refused
+    fn init(&mut self, json: &Path) -> Result<(), Error> {
+        match user_version(&self.conn)? {
+            VERSION => return Ok(()),
+            version if version > VERSION => return Err(newer(version)),
+            _ => {}
+        }
+
+        // Other instance might be initializing the database too
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        match user_version(&tx)? {
+            0 => {}
+            1 => {
+                tx.execute_batch(UPGRADE_V2)?;
+                tx.pragma_update(None, "user_version", VERSION)?;
+                tx.commit()?;
+                return Ok(());
+            }
+            VERSION => return Ok(()),
+            version => return Err(newer(version)),
+        }
+
+        let stats = if json.exists() {
+            let mut stats =
+                JsonStorage::new(json.to_owned(), self.backups).load()?;
+            stats.prepare();
+            Some(stats)
+        } else {
+            None
+        };
+
+        tx.execute_batch(SCHEMA)?;
+        if let Some(stats) = stats {
+            for name in stats.get_sessions(SessionSort::Custom) {
+                let session = &stats.sessions[&name];
+                upsert_session(&tx, &name, session)?;
+                let id = session_id(&tx, &name)?.unwrap_or_default();
+                for stat in session.stats.iter() {
+                    insert_solve(&tx, id, stat)?;
+                }
+            }
+            write_order(&tx, &stats)?;
+        }
+        tx.pragma_update(None, "user_version", VERSION)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&mut self) -> Result<Stats, Error> {
+        let tx = self.conn.transaction()?;
+        let stats = read(&tx)?;
+        self.data_version = data_version(&tx)?;
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    /// Applies each change as a row change in one transaction
+    fn save(
+        &mut self,
+        stats: &mut Stats,
+        events: &[Event],
+    ) -> Result<(), Error> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        // Other instances can't write until the transaction ends, so the
+        // backup is consistent and no changes are missed when merging
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        backups::backup(&self.path, self.backups)?;
+        if data_version(&tx)? != self.data_version {
+            stats.merge(read(&tx)?, events);
+        }
+
+        let mut reorder = false;
+        for event in events {
+            match event {
+                Event::Add { session, stat } => {
+                    if let Some(id) = session_id(&tx, session)? {
+                        insert_solve(&tx, id, stat)?;
+                    }
+                }
+                Event::Remove { session, id } => {
+                    let (solve, id) = solve_filter(id);
+                    tx.execute(
+                        &format!(
+                            "DELETE FROM solves WHERE {solve} AND session_id =
+                            (SELECT id FROM sessions WHERE name = ?1)"
+                        ),
+                        params![session, id],
+                    )?;
+                }
+                Event::Penalty {
+                    session,
+                    id,
+                    penalty,
+                } => {
+                    let (solve, id) = solve_filter(id);
+                    tx.execute(
+                        &format!(
+                            "UPDATE solves SET penalty = ?3 WHERE {solve} AND
+                            session_id =
+                            (SELECT id FROM sessions WHERE name = ?1)"
+                        ),
+                        params![session, id, penalty_to_sql(*penalty)],
+                    )?;
+                }
+                Event::Comment {
+                    session,
+                    id,
+                    comment,
+                } => {
+                    let (solve, id) = solve_filter(id);
+                    tx.execute(
+                        &format!(
+                            "UPDATE solves SET comment = ?3 WHERE {solve} AND
+                            session_id =
+                            (SELECT id FROM sessions WHERE name = ?1)"
+                        ),
+                        params![session, id, comment],
+                    )?;
+                }
+                Event::CreateSession {
+                    session,
+                    scramble_type,
+                } => {
+                    let new = Session::new(scramble_type);
+                    let current = stats.get_session(session).unwrap_or(&new);
+                    upsert_session(&tx, session, current)?;
+                    reorder = true;
+                }
+                Event::RenameSession { session, name } => {
+                    tx.execute(
+                        "UPDATE sessions SET name = ?2 WHERE name = ?1",
+                        params![session, name],
+                    )?;
+                }
+                Event::RemoveSession { session } => {
+                    tx.execute(
+                        "DELETE FROM solves WHERE
+                        session_id = (SELECT id FROM sessions WHERE name = ?1)",
+                        [session],
+                    )?;
+                    tx.execute(
+                        "DELETE FROM sessions WHERE name = ?1",
+                        [session],
+                    )?;
+                    reorder = true;
+                }
+                Event::UpdateSession { session } => {
+                    if let Some(current) = stats.get_session(session) {
+                        upsert_session(&tx, session, current)?;
+                    }
+                }
+                Event::Reorder => reorder = true,
+            }
+        }
+        if reorder {
+            write_order(&tx, stats)?;
+        }
+        tx.commit()?;
+        self.data_version = data_version(&self.conn)?;
+        Ok(())
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn restore(&mut self, index: usize) -> Result<(), Error> {
+        let backup = backups::read_backup(&self.path, index)?;
+        if !backup.starts_with(b"SQLite format 3\0") {
+            return Err(Error::Msg(format!(
+                "backup {index} isn't a SQLite database"
+            )));
+        }
+
+        // Closes the database, so that it can be replaced
+        let conn =
+            std::mem::replace(&mut self.conn, Connection::open_in_memory()?);
+        conn.close().map_err(|(_, e)| e)?;
+
+        backups::rotate(&self.path, self.backups.max(1))?;
+        write_atomic(&self.path, &backup)?;
+        self.conn = Connection::open(&self.path)?;
+        self.conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(())
+    }
+}
+
+/// Reads all stats from the database
+fn read(conn: &Connection) -> Result<Stats, Error> {
+    let mut query = conn.prepare(
+        "SELECT id, name, scramble_type, inspection, averages, last_used
+        FROM sessions ORDER BY position",
+    )?;
+    let rows = query
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, bool>(3)?,
+                r.get::<_, String>(4)?,
+                r.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sessions = Vec::new();
+    for (id, name, scramble_type, inspection, averages, last_used) in rows {
+        let mut session = Session::new(&scramble_type);
+        session.inspection = inspection;
+        session.averages = serde_json::from_str(&averages)?;
+        session.last_used =
+            last_used.map(|d| datetime_from_sql(&d)).transpose()?;
+        session.stats = solves(conn, id)?;
+        sessions.push((name, session));
+    }
+    Ok(Stats::from_sessions(sessions))
+}
+
+/// Gets solves of the session with given id, newest first
+fn solves(conn: &Connection, session_id: i64) -> Result<Vec<Stat>, Error> {
+    let mut query = conn.prepare_cached(
+        "SELECT stat_id, datetime, time, penalty, scramble, comment
+        FROM solves WHERE session_id = ?1 ORDER BY datetime DESC, id DESC",
+    )?;
+    let rows = query.query_map([session_id], |r| {
+        Ok((
+            r.get::<_, i64>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, i64>(2)?,
+            r.get::<_, String>(3)?,
+            r.get::<_, String>(4)?,
+            r.get::<_, String>(5)?,
+        ))
+    })?;
+
+    rows.map(|row| {
+        let (id, datetime, time, penalty, scramble, comment) = row?;
+        Ok(Stat::stored(
+            id as u64,
+            Duration::from_nanos(time as u64),
+            penalty_from_sql(&penalty)?,
+            scramble,
+            comment,
+            datetime_from_sql(&datetime)?,
+        ))
+    })
+    .collect()
+}
+
+/// Gets error refusing database of given newer version
+fn newer(version: i32) -> Error {
+    Error::Msg(format!(
+        "stats database has version {version}, but this version of rsTimer \
+        supports only versions up to {VERSION}, please update rsTimer"
+    ))
+}
+
+/// Gets version of the database schema
+fn user_version(conn: &Connection) -> Result<i32, Error> {
+    Ok(conn.query_row("PRAGMA user_version", [], |r| r.get(0))?)
+}
+
+/// Gets data version of the database, which changes when other connection
+/// commits changes
+fn data_version(conn: &Connection) -> Result<i64, Error> {
+    Ok(conn.query_row("PRAGMA data_version", [], |r| r.get(0))?)
+}
+
+/// Gets id of the session with given name
+fn session_id(tx: &Transaction, name: &str) -> Result<Option<i64>, Error> {
+    Ok(tx
+        .query_row("SELECT id FROM sessions WHERE name = ?1", [name], |r| {
+            r.get(0)
+        })
+        .optional()?)
+}
+
+/// Inserts given session or updates its settings when it already exists
+fn upsert_session(
+    tx: &Transaction,
+    name: &str,
+    session: &Session,
+) -> Result<(), Error> {
+    tx.execute(
+        "INSERT INTO sessions
+        (name, position, scramble_type, inspection, averages, last_used)
+        VALUES (?1, (SELECT COUNT(*) FROM sessions), ?2, ?3, ?4, ?5)
+        ON CONFLICT(name) DO UPDATE SET scramble_type = ?2,
+        inspection = ?3, averages = ?4, last_used = ?5",
+        params![
+            name,
+            session.scramble_type,
+            session.inspection,
+            serde_json::to_string(&session.averages)?,
+            session.last_used.as_ref().map(datetime_to_sql),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Inserts given solve to the session with given id, solve with the same
+/// id that's already stored is skipped
+fn insert_solve(
+    tx: &Transaction,
+    session_id: i64,
+    stat: &Stat,
+) -> Result<(), Error> {
+    tx.execute(
+        "INSERT OR IGNORE INTO solves
+        (session_id, stat_id, datetime, time, penalty, scramble, comment)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            session_id,
+            stat.id as i64,
+            datetime_to_sql(&stat.datetime),
+            stat.time.as_nanos() as i64,
+            penalty_to_sql(stat.penalty),
+            stat.scramble(),
+            stat.comment(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Gets condition matching the solve with given id and the value of its
+/// `?2` parameter. Solves identified by the date and time come only from
+/// journals written before solves had ids
+fn solve_filter(id: &SolveId) -> (&'static str, SqlValue) {
+    match id {
+        SolveId::Id(id) => ("stat_id = ?2", SqlValue::Integer(*id as i64)),
+        SolveId::Datetime(datetime) => {
+            ("datetime = ?2", SqlValue::Text(datetime_to_sql(datetime)))
+        }
+    }
+}
+
+/// Writes user defined order of the sessions
+fn write_order(tx: &Transaction, stats: &Stats) -> Result<(), Error> {
+    let sessions = stats.get_sessions(SessionSort::Custom);
+    for (position, name) in sessions.iter().enumerate() {
+        tx.execute(
+            "UPDATE sessions SET position = ?2 WHERE name = ?1",
+            params![name, position as i64],
+        )?;
+    }
+    Ok(())
+}
+
+/// Converts datetime to the text stored in the database. UTC with fixed
+/// precision is used, so that the text sorts chronologically
+fn datetime_to_sql(datetime: &DateTime<Local>) -> String {
+    datetime
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+/// Parses datetime stored in the database
+fn datetime_from_sql(text: &str) -> Result<DateTime<Local>, Error> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|d| d.with_timezone(&Local))
+        .map_err(|e| Error::Msg(format!("invalid date '{text}': {e}")))
+}
+
+/// Converts penalty to the text stored in the database
+fn penalty_to_sql(penalty: Penalty) -> &'static str {
+    match penalty {
+        Penalty::None => "none",
+        Penalty::PlusTwo => "+2",
+        Penalty::Dnf => "dnf",
+    }
+}
+
+/// Parses penalty stored in the database
+fn penalty_from_sql(text: &str) -> Result<Penalty, Error> {
+    match text {
+        "none" => Ok(Penalty::None),
+        "+2" => Ok(Penalty::PlusTwo),
+        "dnf" => Ok(Penalty::Dnf),
+        _ => Err(Error::Msg(format!("invalid penalty '{text}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cmp::Reverse,
+        fs::{create_dir_all, remove_dir_all, write},
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    use rusqlite::Connection;
+
+    use super::{user_version, SqliteStorage, VERSION};
+    use crate::stats::{
+        event::{Event, SolveId},
+        stat::{Penalty, Stat},
+        stats::Stats,
+    };
+    use crate::storage::{json::JsonStorage, Storage};
+
+    /// Schema of the version 1, where solves were identified by the date
+    /// and time
+    const SCHEMA_V1: &str = "
+    CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        position INTEGER NOT NULL,
+        scramble_type TEXT NOT NULL,
+        inspection INTEGER NOT NULL,
+        averages TEXT NOT NULL,
+        last_used TEXT
+    );
+    CREATE TABLE solves (
+        id INTEGER PRIMARY KEY,
+        session_id INTEGER NOT NULL REFERENCES sessions(id),
+        datetime TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        penalty TEXT NOT NULL,
+        scramble TEXT NOT NULL,
+        comment TEXT NOT NULL
+    );
+    CREATE UNIQUE INDEX solves_session_datetime
+        ON solves(session_id, datetime);
+    INSERT INTO sessions VALUES (1, '3x3', 0, '3x3x3', 1, '[\"ao5\"]', NULL);
+    INSERT INTO solves VALUES
+        (1, 1, '2024-05-02T07:14:05.123456789Z', 12345000000, '+2', 'R U',
+        ''),
+        (2, 1, '2024-05-02T07:15:00.000000000Z', 10980000000, 'dnf', 'F D',
+        'PLL skip');
+    PRAGMA user_version = 1;
+    ";
+
+    const STATS_V2: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/migrate/stats_v2.json"
+    ));
+
+    /// Creates empty directory for the test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("rstimer-{}-sqlite-{name}", std::process::id()));
+        _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Opens the database in given directory, JSON stats are migrated from
+    /// the `stats` file in it
+    fn open(dir: &Path) -> SqliteStorage {
+        SqliteStorage::open(dir.join("stats.db"), &dir.join("stats"), 3)
+            .unwrap()
+    }
+
+    fn stat(millis: u64, penalty: Penalty, comment: &str) -> Stat {
+        Stat::new(
+            Duration::from_millis(millis),
+            penalty,
+            "R U R' U'".to_owned(),
+            comment.to_owned(),
+        )
+    }
+
+    /// Checks that the stats have the same solves
+    fn assert_solves(stats: &[Stat], expected: &[&Stat]) {
+        assert_eq!(stats.len(), expected.len());
+        for (stat, expected) in stats.iter().zip(expected) {
+            assert_eq!(stat.id, expected.id);
+            assert_eq!(stat.time, expected.time);
+            assert_eq!(stat.penalty, expected.penalty);
+            assert_eq!(stat.scramble(), expected.scramble());
+            assert_eq!(stat.comment(), expected.comment());
+            assert_eq!(stat.datetime, expected.datetime);
+        }
+    }
+
+    /// Saves new session with given solves
+    fn save_solves(storage: &mut SqliteStorage, stats: &[&Stat]) {
+        let mut events = vec![Event::CreateSession {
+            session: "3x3".to_owned(),
+            scramble_type: "3x3x3".to_owned(),
+        }];
+        events.extend(stats.iter().map(|s| Event::Add {
+            session: "3x3".to_owned(),
+            stat: (*s).clone(),
+        }));
+        storage.save(&mut Stats::default(), &events).unwrap();
+    }
+
+    #[test]
+    fn create_schema() {
+        let dir = temp_dir("schema");
+        let storage = open(&dir);
+        assert_eq!(user_version(&storage.conn).unwrap(), VERSION);
+
+        // Solves of the session are read using the index
+        let plan: Vec<String> = storage
+            .conn
+            .prepare(
+                "EXPLAIN QUERY PLAN SELECT * FROM solves WHERE session_id = 1
+                ORDER BY datetime DESC, id DESC",
+            )
+            .unwrap()
+            .query_map([], |r| r.get(3))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(plan.iter().any(|p| p.contains("solves_session_datetime")));
+        assert!(!plan.iter().any(|p| p.contains("TEMP B-TREE")));
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn save_load() {
+        let dir = temp_dir("save");
+        let mut storage = open(&dir);
+        let first = stat(12_340, Penalty::None, "");
+        let mut second = stat(9_870, Penalty::PlusTwo, "lucky, \"nice\"");
+        let mut third = stat(10_000, Penalty::Dnf, "");
+        // Distinct solves set at the same time are both kept
+        second.datetime = first.datetime + Duration::from_secs(1);
+        third.datetime = second.datetime;
+        save_solves(&mut storage, &[&first, &second, &third]);
+        // Solve which is already stored is skipped
+        save_solves(&mut storage, &[&first]);
+
+        let stats = open(&dir).load().unwrap();
+        let session = stats.get_session("3x3").unwrap();
+        let expected = if session.stats[0].id == second.id {
+            [&second, &third, &first]
+        } else {
+            [&third, &second, &first]
+        };
+        assert_solves(&session.stats, &expected);
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn change_solves() {
+        let dir = temp_dir("change");
+        let mut storage = open(&dir);
+        let first = stat(12_340, Penalty::None, "");
+        let mut second = stat(9_870, Penalty::None, "");
+        second.datetime = first.datetime + Duration::from_secs(1);
+        save_solves(&mut storage, &[&first, &second]);
+
+        let session = "3x3".to_owned();
+        let events = [
+            Event::Remove {
+                session: session.clone(),
+                id: SolveId::Id(second.id),
+            },
+            Event::Penalty {
+                session: session.clone(),
+                id: SolveId::Id(first.id),
+                penalty: Penalty::Dnf,
+            },
+            Event::Comment {
+                session,
+                id: SolveId::Id(first.id),
+                comment: "pop".to_owned(),
+            },
+        ];
+        storage.save(&mut Stats::default(), &events).unwrap();
+
+        let stats = open(&dir).load().unwrap();
+        let stats = &stats.get_session("3x3").unwrap().stats;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].id, first.id);
+        assert_eq!(stats[0].penalty, Penalty::Dnf);
+        assert_eq!(stats[0].comment(), "pop");
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn upgrade_v1() {
+        let dir = temp_dir("upgrade");
+        Connection::open(dir.join("stats.db"))
+            .unwrap()
+            .execute_batch(SCHEMA_V1)
+            .unwrap();
+
+        let mut storage = open(&dir);
+        assert_eq!(user_version(&storage.conn).unwrap(), VERSION);
+        let stats = storage.load().unwrap();
+        let stats = &stats.get_session("3x3").unwrap().stats;
+        assert_eq!(stats.iter().map(|s| s.id).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(stats[0].penalty, Penalty::Dnf);
+        assert_eq!(stats[0].comment(), "PLL skip");
+        assert_eq!(stats[1].time, Duration::from_millis(12_345));
+
+        // Solves set at the same time can be stored after the upgrade
+        let mut same = stat(8_000, Penalty::None, "");
+        same.datetime = stats[0].datetime;
+        let add = Event::Add {
+            session: "3x3".to_owned(),
+            stat: same,
+        };
+        storage.save(&mut Stats::default(), &[add]).unwrap();
+        let stats = open(&dir).load().unwrap();
+        assert_eq!(stats.get_session("3x3").unwrap().stats.len(), 3);
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn migrate_json() {
+        let dir = temp_dir("json");
+        write(dir.join("stats"), STATS_V2).unwrap();
+        let mut json = JsonStorage::new(dir.join("stats"), 3);
+        let expected = json.load().unwrap();
+
+        let stats = open(&dir).load().unwrap();
+        assert_eq!(stats.get_sessions(Default::default()).len(), 2);
+        for name in ["2x2", "3x3"] {
+            let session = stats.get_session(name).unwrap();
+            let expected = expected.get_session(name).unwrap();
+            assert_eq!(session.scramble_type, expected.scramble_type);
+            assert_eq!(session.inspection, expected.inspection);
+            assert_eq!(session.averages, expected.averages);
+            assert_eq!(session.last_used, expected.last_used);
+            // Database gives the newest solves first
+            let mut expected: Vec<_> = expected.stats.iter().collect();
+            expected.sort_by_key(|s| Reverse(s.datetime));
+            assert_solves(&session.stats, &expected);
+        }
+        _ = remove_dir_all(dir);
+    }
+
+    #[test]
+    fn newer_version_fails() {
+        let dir = temp_dir("newer");
+        Connection::open(dir.join("stats.db"))
+            .unwrap()
+            .pragma_update(None, "user_version", VERSION + 1)
+            .unwrap();
+
+        let res = SqliteStorage::open(dir.join("stats.db"), &dir.join("s"), 3);
+        assert!(res.is_err_and(|e| e.to_string().contains("update rsTimer")));
+        _ = remove_dir_all(dir);
+    }
+}
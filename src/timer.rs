@@ -2,20 +2,29 @@ use std::time::{Duration, Instant};
 
 use crossterm::event::{poll, read, Event, KeyCode, KeyEvent};
 use termint::{
-    enums::Color,
+    enums::{Color, Modifier},
     geometry::Constraint,
     style::Style,
-    widgets::{Block, Border, Layout, List, Spacer},
+    widgets::{Block, Border, Layout, List, Spacer, Span},
 };
 
 use crate::{
     app::{App, Screen},
     asci::time_layout,
     error::Error,
-    stats::stat::Stat,
-    widgets::raw_span::RawSpan,
+    stats::{
+        average::Average,
+        stat::{Penalty, SolveTime, Stat},
+    },
+    widgets::{center_span::CenterSpan, raw_span::RawSpan},
 };
 
+/// How long the new personal best message flashes
+const PB_FLASH: Duration = Duration::from_secs(3);
+
+/// Minimal width of the stats sidebar
+const MIN_STATS_WIDTH: usize = 17;
+
 /// Idle and running timer implementation
 impl App {
     /// Renders timer screen
@@ -72,6 +81,8 @@ impl App {
                 }
                 return self.render_timer();
             }
+            KeyCode::Enter => self.screen = Screen::Detail,
+            KeyCode::Char('c') | KeyCode::Char('C') => self.edit_comment(),
             KeyCode::Char('s') | KeyCode::Char('S') => {
                 self.screen = Screen::Sessions
             }
@@ -80,7 +91,29 @@ impl App {
                     scramble.generate();
                 }
             }
-            KeyCode::Char(' ') => self.start_timer()?,
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                return self.toggle_penalty(Penalty::PlusTwo)
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                return self.toggle_penalty(Penalty::Dnf)
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.screen = Screen::Averages
+            }
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                let session = self.session.as_ref().unwrap();
+                if let Some(session) = self.stats.get_session_mut(session) {
+                    session.inspection = !session.inspection;
+                    self.stats.save()?;
+                }
+            }
+            KeyCode::Char(' ') => {
+                if !self.inspection() {
+                    self.start_timer(Penalty::None)?;
+                } else if let Some(penalty) = self.start_inspection()? {
+                    self.start_timer(penalty)?;
+                }
+            }
             KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
                 return Err(Error::Exit)
             }
@@ -95,35 +128,53 @@ impl App {
         time: f64,
         scramble: &str,
     ) -> Result<(), Error> {
-        let mut slayout = Layout::horizontal().center();
-        slayout.add_child(scramble, Constraint::Min(0));
-
+        // Long scrambles of the big cubes are wrapped
         let mut timer = Layout::vertical();
-        timer.add_child(slayout, Constraint::Min(1));
+        timer.add_child(CenterSpan::new(scramble), Constraint::Min(1));
         timer.add_child(Spacer::new(), Constraint::Fill);
 
         let (time, height) = time_layout(time, 3, &self.config.font);
         timer.add_child(time, Constraint::Length(height));
         timer.add_child(Spacer::new(), Constraint::Length(1));
-        self.timer_avg(&mut timer, 5);
-        self.timer_avg(&mut timer, 12);
+        self.timer_pb(&mut timer);
+        let session = self.session.as_ref().unwrap();
+        if let Some(session) = self.stats.get_session(session) {
+            for average in session.averages.iter() {
+                self.timer_avg(&mut timer, *average);
+            }
+        }
 
         timer.add_child(Spacer::new(), Constraint::Fill);
 
+        self.render_timer_screen(timer)
+    }
+
+    /// Renders given timer layout together with stats and help
+    pub fn render_timer_screen(&mut self, timer: Layout) -> Result<(), Error> {
         let mut layout = Layout::horizontal();
-        layout.add_child(self.timer_stats(), Constraint::Length(17));
+        let (stats, width) = self.timer_stats();
+        layout.add_child(stats, Constraint::Length(width));
         layout.add_child(timer, Constraint::Fill);
 
         let mut main = Layout::vertical();
         main.add_child(layout, Constraint::Fill);
-        main.add_child(self.timer_help(), Constraint::Length(1));
+        if let Some(warning) = self.instance_warning() {
+            main.add_child(warning, Constraint::Length(1));
+        }
+        match self.screen {
+            Screen::Comment => {
+                main.add_child(self.comment_input(), Constraint::Length(1))
+            }
+            _ => main.add_child(self.timer_help(), Constraint::Length(1)),
+        }
 
         self.term.render(main)?;
         Ok(())
     }
 
-    /// Stats the running timer loop
-    fn start_timer(&mut self) -> Result<(), Error> {
+    /// Stats the running timer loop, the solve gets given penalty
+    fn start_timer(&mut self, penalty: Penalty) -> Result<(), Error> {
+        self.pb_flash = None;
         let start = Instant::now();
 
         let wait_time = Duration::from_secs_f64(0.001);
@@ -142,16 +193,45 @@ impl App {
 
         self.time = start.elapsed();
         if let Some(scramble) = &mut self.scramble {
-            self.stats.add(
-                Stat::new(self.time, scramble.get().to_owned(), String::new()),
+            let records = self.stats.add(
+                Stat::new(
+                    self.time,
+                    penalty,
+                    scramble.get().to_owned(),
+                    String::new(),
+                ),
                 self.session.as_ref().unwrap(),
             )?;
+            if !records.is_empty() {
+                self.pb_flash = Some((Instant::now(), records));
+            }
             self.stats.save()?;
             scramble.generate();
         }
         Ok(())
     }
 
+    /// Toggles penalty of the currently selected stat
+    fn toggle_penalty(&mut self, penalty: Penalty) -> Result<(), Error> {
+        if let Some(sel) = self.stats_state.borrow().selected {
+            self.stats.toggle_penalty(
+                sel,
+                self.session.as_ref().unwrap(),
+                penalty,
+            );
+            self.stats.save()?;
+        }
+        self.render_timer()
+    }
+
+    /// Checks whether inspection is enabled in the current session
+    fn inspection(&self) -> bool {
+        self.session
+            .as_ref()
+            .and_then(|s| self.stats.get_session(s))
+            .is_some_and(|s| s.inspection)
+    }
+
     /// Key listener when timer is running
     fn listen_run_timer(&mut self) -> Result<bool, Error> {
         let Event::Key(KeyEvent { code, .. }) = read()? else {
@@ -161,60 +241,114 @@ impl App {
         Ok(!matches!(code, KeyCode::Char(' ')))
     }
 
-    /// Renders average of n
-    fn timer_avg(&self, layout: &mut Layout, n: usize) {
-        if let Some(avg) = self.stats.avg_of(self.session.as_ref().unwrap(), n)
-        {
+    /// Renders given average
+    fn timer_avg(&self, layout: &mut Layout, average: Average) {
+        let session = self.session.as_ref().unwrap();
+        if let Some(avg) = self.stats.average(session, average) {
+            let name = average.to_string().to_uppercase();
             let mut center = Layout::horizontal().center();
-            center.add_child(
-                format!("AO{}: {:.3}", n, avg.as_secs_f64()),
-                Constraint::Min(0),
-            );
+            center.add_child(format!("{name}: {avg}"), Constraint::Min(0));
             layout.add_child(center, Constraint::Min(0));
         }
     }
 
+    /// Renders flashing personal best message, when new one was set
+    fn timer_pb(&mut self, layout: &mut Layout) {
+        let Some((start, records)) = &self.pb_flash else {
+            return;
+        };
+
+        let elapsed = start.elapsed();
+        if elapsed >= PB_FLASH {
+            self.pb_flash = None;
+            return;
+        }
+
+        let records: Vec<String> =
+            records.iter().map(|r| r.to_string()).collect();
+        let color = if elapsed.as_millis() / 250 % 2 == 0 {
+            Color::Yellow
+        } else {
+            Color::Magenta
+        };
+
+        let mut center = Layout::horizontal().center();
+        center.add_child(
+            Span::new(format!("New PB: {}!", records.join(", ")))
+                .fg(color)
+                .modifier(Modifier::BOLD),
+            Constraint::Min(0),
+        );
+        layout.add_child(center, Constraint::Min(0));
+    }
+
     /// Renders timer stats
-    fn timer_stats(&self) -> Block {
+    ///
+    /// **Returns:**
+    /// * Stats block and its width fitting the longest line
+    fn timer_stats(&self) -> (Block, usize) {
         let name = self.session.clone().unwrap_or("".to_string());
         let mut block = Block::vertical().title(name.as_str());
 
-        let stats: Vec<String> = self.stats.sessions
-            [self.session.as_ref().unwrap()]
-        .stats
-        .iter()
-        .map(|i| format!("{:.3}", i.time.as_secs_f64()))
-        .collect();
+        let session = &self.stats.sessions[self.session.as_ref().unwrap()];
+        let stats: Vec<String> = session
+            .stats
+            .iter()
+            .map(|i| {
+                if i.comment().is_empty() {
+                    i.to_string()
+                } else {
+                    format!("{i} *")
+                }
+            })
+            .collect();
 
         if stats.is_empty() {
             block.add_child("No times set yet...", Constraint::Fill);
-        } else {
-            block.add_child(
-                format!("Solves: {}", stats.len()),
-                Constraint::Min(0),
-            );
-            block.add_child(
-                format!(
-                    "Mean: {:.3}",
-                    self.stats
-                        .avg(self.session.as_ref().unwrap())
-                        .unwrap_or(Duration::from_secs(0))
-                        .as_secs_f64()
-                ),
-                Constraint::Min(0),
-            );
-            block.add_child(
-                Block::vertical().borders(Border::BOTTOM),
-                Constraint::Length(1),
-            );
-            block.add_child(
-                List::new(stats, self.stats_state.clone())
-                    .selected_style(Style::new().fg(Color::Cyan))
-                    .auto_scroll(),
-                Constraint::Fill,
-            );
+            return (block, MIN_STATS_WIDTH);
         }
-        block
+
+        let mut lines = vec![
+            format!("Solves: {}", stats.len()),
+            format!(
+                "Mean: {}",
+                self.stats
+                    .avg(self.session.as_ref().unwrap())
+                    .unwrap_or(SolveTime::Time(Duration::ZERO))
+            ),
+        ];
+        if let Some(single) = session.records().single() {
+            lines.push(format!("PB single: {single}"));
+        }
+        for average in session.averages.iter() {
+            if let Some(best) = session.records().average(*average) {
+                lines.push(format!("PB {average}: {best}"));
+            }
+        }
+
+        // Sidebar is wide enough for the longest line including borders
+        let width = lines
+            .iter()
+            .chain(stats.iter())
+            .map(|l| l.chars().count() + 2)
+            .max()
+            .unwrap_or_default()
+            .max(MIN_STATS_WIDTH);
+
+        for line in lines {
+            block.add_child(line, Constraint::Min(0));
+        }
+        block.add_child(
+            Block::vertical().borders(Border::BOTTOM),
+            Constraint::Length(1),
+        );
+        block.add_child(
+            List::new(stats, self.stats_state.clone())
+                .selected_style(Style::new().fg(Color::Cyan))
+                .auto_scroll(),
+            Constraint::Fill,
+        );
+        (block, width)
     }
 
     /// Renders timer help
@@ -236,10 +370,36 @@ impl App {
             RawSpan::new("[↓/j]Prev. stat ").fg(Color::Gray),
             Constraint::Min(0),
         );
+        layout.add_child(
+            RawSpan::new("[Enter]Stat detail ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[c]Comment ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
         layout.add_child(
             RawSpan::new("[Del]Delete stat ").fg(Color::Gray),
             Constraint::Min(0),
         );
+        layout.add_child(
+            RawSpan::new("[p]+2 ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[d]DNF ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        layout.add_child(
+            RawSpan::new("[a]Averages ").fg(Color::Gray),
+            Constraint::Min(0),
+        );
+        let inspection = if self.inspection() { "on" } else { "off" };
+        layout.add_child(
+            RawSpan::new(format!("[i]Inspection ({inspection}) "))
+                .fg(Color::Gray),
+            Constraint::Min(0),
+        );
         layout.add_child(
             RawSpan::new("[→|l]Next font ").fg(Color::Gray),
             Constraint::Min(0),
@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::Error,
+    stats::{
+        stat::{Penalty, Stat},
+        stats::Stats,
+    },
+    transfer::{Import, Imported},
+};
+
+/// csTimer scramble types and the corresponding scramble types. The csTimer
+/// type is used for export, on import types are matched by the first three
+/// characters (`333oh` is 3x3x3 as well)
+const SCRAMBLE_TYPES: &[(&str, &str)] = &[
+    ("222so", "2x2x2"),
+    ("333", "3x3x3"),
+    ("444wca", "4x4x4"),
+    ("555wca", "5x5x5"),
+    ("666wca", "6x6x6"),
+    ("777wca", "7x7x7"),
+    ("pyrso", "Pyraminx"),
+    ("skbso", "Skewb"),
+    ("mgmp", "Megaminx"),
+    ("clkwca", "Clock"),
+    ("sqrs", "Square-1"),
+];
+
+/// csTimer penalty of +2 solve (in milliseconds)
+const PLUS_TWO: i64 = 2000;
+
+/// csTimer penalty of DNF solve
+const DNF: i64 = -1;
+
+/// Reads sessions from the csTimer export. Sessions are stored under the
+/// `session<n>` keys, their names and scramble types are stored in the
+/// `sessionData` property as JSON string
+pub fn read(text: &str) -> Result<Import, Error> {
+    let data: Map<String, Value> = serde_json::from_str(text)?;
+    let info: Map<String, Value> = data
+        .get("properties")
+        .and_then(|p| p.get("sessionData"))
+        .and_then(Value::as_str)
+        .and_then(|d| serde_json::from_str(d).ok())
+        .unwrap_or_default();
+
+    let mut keys: Vec<(u64, &String)> = data
+        .keys()
+        .filter_map(|k| Some((k.strip_prefix("session")?.parse().ok()?, k)))
+        .collect();
+    keys.sort();
+
+    let mut import = Import::default();
+    for (id, key) in keys {
+        let info = info.get(&id.to_string());
+        let name = match info.and_then(|i| i.get("name")) {
+            Some(Value::String(name)) => name.clone(),
+            Some(name) => name.to_string(),
+            None => format!("Session {id}"),
+        };
+
+        let scr_type = info
+            .and_then(|i| i.get("opt"))
+            .and_then(|o| o.get("scrType"))
+            .and_then(Value::as_str)
+            .unwrap_or("333");
+        let scramble_type = match scramble_type(scr_type) {
+            Some(scramble_type) => scramble_type,
+            None => {
+                import.warnings.push(format!(
+                    "session '{name}': unknown scramble type '{scr_type}', \
+                    3x3x3 is used"
+                ));
+                "3x3x3"
+            }
+        };
+
+        // Older versions of csTimer store solves as JSON string
+        let solves = match &data[key] {
+            Value::String(s) => serde_json::from_str(s)?,
+            solves => solves.clone(),
+        };
+        let mut stats = Vec::new();
+        for (i, solve) in solves.as_array().into_iter().flatten().enumerate() {
+            match read_solve(solve) {
+                Some(stat) => stats.push(stat),
+                None => import.warnings.push(format!(
+                    "session '{name}': invalid solve {} skipped",
+                    i + 1
+                )),
+            }
+        }
+
+        import.sessions.push(Imported {
+            name,
+            scramble_type: scramble_type.to_owned(),
+            stats,
+        });
+    }
+    Ok(import)
+}
+
+/// Writes given sessions in the csTimer export format
+pub fn write(stats: &Stats, sessions: &[String]) -> Result<String, Error> {
+    let mut data = Map::new();
+    let mut info = Map::new();
+    for (i, name) in sessions.iter().enumerate() {
+        let id = i + 1;
+        let session = stats
+            .get_session(name)
+            .ok_or(Error::Msg(format!("session '{name}' doesn't exist")))?;
+
+        // csTimer stores solves from the oldest
+        let solves: Vec<Value> =
+            session.stats.iter().rev().map(write_solve).collect();
+        data.insert(format!("session{id}"), Value::Array(solves));
+
+        let mut session_info = json!({ "name": name, "rank": id });
+        if let Some(scr_type) = cstimer_type(&session.scramble_type) {
+            session_info["opt"] = json!({ "scrType": scr_type });
+        }
+        info.insert(id.to_string(), session_info);
+    }
+
+    data.insert(
+        "properties".to_string(),
+        json!({
+            "sessionN": sessions.len(),
+            "sessionData": serde_json::to_string(&info)?,
+        }),
+    );
+    Ok(serde_json::to_string(&data)?)
+}
+
+/// Reads solve stored as `[[penalty, time], scramble, comment, timestamp]`,
+/// time is in milliseconds and timestamp in seconds
+fn read_solve(solve: &Value) -> Option<Stat> {
+    let solve = solve.as_array()?;
+    let time = solve.first()?.as_array()?;
+    let penalty = match time.first()?.as_i64()? {
+        0 => Penalty::None,
+        p if p > 0 => Penalty::PlusTwo,
+        _ => Penalty::Dnf,
+    };
+    let millis = time.get(1)?.as_u64()?;
+    let scramble = solve.get(1).and_then(Value::as_str).unwrap_or_default();
+    let comment = solve.get(2).and_then(Value::as_str).unwrap_or_default();
+    let timestamp = solve.get(3)?.as_i64()?;
+
+    let mut stat = Stat::new(
+        Duration::from_millis(millis),
+        penalty,
+        scramble.to_owned(),
+        comment.to_owned(),
+    );
+    stat.datetime =
+        DateTime::from_timestamp(timestamp, 0)?.with_timezone(&Local);
+    Some(stat)
+}
+
+/// Writes solve in the csTimer format
+fn write_solve(stat: &Stat) -> Value {
+    let penalty = match stat.penalty {
+        Penalty::None => 0,
+        Penalty::PlusTwo => PLUS_TWO,
+        Penalty::Dnf => DNF,
+    };
+    json!([
+        [penalty, stat.time.as_millis() as u64],
+        stat.scramble(),
+        stat.comment(),
+        stat.datetime.timestamp(),
+    ])
+}
+
+/// Gets scramble type corresponding to given csTimer scramble type
+fn scramble_type(scr_type: &str) -> Option<&'static str> {
+    SCRAMBLE_TYPES
+        .iter()
+        .find(|(cs, _)| scr_type.get(..3) == cs.get(..3))
+        .map(|(_, scramble_type)| *scramble_type)
+}
+
+/// Gets csTimer scramble type corresponding to given scramble type
+fn cstimer_type(scramble_type: &str) -> Option<&'static str> {
+    SCRAMBLE_TYPES
+        .iter()
+        .find(|(_, t)| *t == scramble_type)
+        .map(|(cs, _)| *cs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::read;
+    use crate::stats::stat::Penalty;
+
+    const EXPORT: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/transfer/cstimer.json"
+    ));
+
+    #[test]
+    fn import() {
+        let import = read(EXPORT).unwrap();
+        assert_eq!(import.sessions.len(), 2);
+        // Solve without timestamp is skipped
+        assert_eq!(import.warnings.len(), 1);
+
+        let main = &import.sessions[0];
+        assert_eq!(main.name, "Main");
+        assert_eq!(main.scramble_type, "3x3x3");
+        let solves: Vec<_> = main
+            .stats
+            .iter()
+            .map(|s| (s.time, s.penalty, s.comment(), s.datetime.timestamp()))
+            .collect();
+        assert_eq!(
+            solves,
+            [
+                (Duration::from_millis(12_345), Penalty::None, "", 1714633445),
+                (
+                    Duration::from_millis(9_870),
+                    Penalty::PlusTwo,
+                    "lucky, \"skip\"",
+                    1714633500
+                ),
+                (
+                    Duration::from_millis(15_020),
+                    Penalty::Dnf,
+                    "pop",
+                    1714633600
+                ),
+            ]
+        );
+        assert_eq!(main.stats[1].scramble(), "F2 D' L");
+
+        // Older csTimer stores solves as JSON string
+        let clock = &import.sessions[1];
+        assert_eq!(clock.name, "Clock");
+        assert_eq!(clock.scramble_type, "Clock");
+        assert_eq!(clock.stats.len(), 1);
+        assert_eq!(clock.stats[0].datetime.timestamp(), 1714640000);
+    }
+}
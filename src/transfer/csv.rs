@@ -0,0 +1,353 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat};
+use csv::{ReaderBuilder, Trim, Writer};
+
+use crate::{
+    error::Error,
+    stats::{
+        stat::{Penalty, Stat},
+        stats::Stats,
+    },
+    transfer::{Import, Imported},
+};
+
+/// Session name used when the file has no session column
+const DEFAULT_SESSION: &str = "Imported";
+
+/// Scramble type used when the file has no scramble type column
+const DEFAULT_SCRAMBLE_TYPE: &str = "3x3x3";
+
+/// Field of the solve stored in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Session,
+    ScrambleType,
+    Date,
+    Time,
+    Penalty,
+    Scramble,
+    Comment,
+}
+
+impl Field {
+    /// All fields in the order of the exported columns
+    const ALL: [Field; 7] = [
+        Field::Session,
+        Field::ScrambleType,
+        Field::Date,
+        Field::Time,
+        Field::Penalty,
+        Field::Scramble,
+        Field::Comment,
+    ];
+
+    /// Gets name of the field, which is also the default column header
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Session => "session",
+            Field::ScrambleType => "scramble_type",
+            Field::Date => "date",
+            Field::Time => "time",
+            Field::Penalty => "penalty",
+            Field::Scramble => "scramble",
+            Field::Comment => "comment",
+        }
+    }
+}
+
+/// Mapping of the fields to the column headers used on import
+#[derive(Debug, Clone, Default)]
+pub struct Columns(HashMap<Field, String>);
+
+impl Columns {
+    /// Gets header of the column containing given field
+    pub fn header(&self, field: Field) -> &str {
+        self.0.get(&field).map_or(field.name(), |h| h.as_str())
+    }
+}
+
+impl FromStr for Columns {
+    type Err = Error;
+
+    /// Parses mapping in format `field=header,field=header`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut columns = HashMap::new();
+        for mapping in s.split(',').filter(|m| !m.trim().is_empty()) {
+            let (name, header) = mapping.split_once('=').ok_or(Error::Msg(
+                format!("invalid column mapping '{mapping}'"),
+            ))?;
+            let field = Field::ALL
+                .into_iter()
+                .find(|f| f.name() == name.trim())
+                .ok_or(Error::Msg(format!("unknown field '{name}'")))?;
+            columns.insert(field, header.trim().to_owned());
+        }
+        Ok(Columns(columns))
+    }
+}
+
+/// Reads solves from the CSV file with header. Columns are found by the
+/// headers given by the column mapping, date and time columns are required
+pub fn read(text: &str, columns: &Columns) -> Result<Import, Error> {
+    let mut reader = ReaderBuilder::new()
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let index = |field: Field| {
+        let header = columns.header(field);
+        headers.iter().position(|h| h.eq_ignore_ascii_case(header))
+    };
+
+    let mut indexes = HashMap::new();
+    for field in Field::ALL {
+        if let Some(i) = index(field) {
+            indexes.insert(field, i);
+        }
+    }
+    for field in [Field::Date, Field::Time] {
+        if !indexes.contains_key(&field) {
+            return Err(Error::Msg(format!(
+                "column '{}' not found",
+                columns.header(field)
+            )));
+        }
+    }
+
+    let mut import = Import::default();
+    let mut sessions = HashMap::new();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                import.warnings.push(format!("line {line}: {e}, skipped"));
+                continue;
+            }
+        };
+        let get = |field| {
+            indexes
+                .get(&field)
+                .and_then(|i| record.get(*i))
+                .unwrap_or_default()
+        };
+
+        let stat = match read_stat(get) {
+            Ok(stat) => stat,
+            Err(e) => {
+                import.warnings.push(format!("line {line}: {e}, skipped"));
+                continue;
+            }
+        };
+
+        let name = Some(get(Field::Session))
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_SESSION);
+        let id = *sessions.entry(name.to_owned()).or_insert_with(|| {
+            let scramble_type = Some(get(Field::ScrambleType))
+                .filter(|s| !s.is_empty())
+                .unwrap_or(DEFAULT_SCRAMBLE_TYPE);
+            import.sessions.push(Imported {
+                name: name.to_owned(),
+                scramble_type: scramble_type.to_owned(),
+                stats: Vec::new(),
+            });
+            import.sessions.len() - 1
+        });
+        import.sessions[id].stats.push(stat);
+    }
+    Ok(import)
+}
+
+/// Writes given sessions to the CSV file, solves are written from the
+/// oldest. Date and time are written with full precision, so the import
+/// gives the same solves
+pub fn write(stats: &Stats, sessions: &[String]) -> Result<String, Error> {
+    let mut writer = Writer::from_writer(Vec::new());
+    writer.write_record(Field::ALL.map(|f| f.name()))?;
+    for name in sessions {
+        let session = stats
+            .get_session(name)
+            .ok_or(Error::Msg(format!("session '{name}' doesn't exist")))?;
+        for stat in session.stats.iter().rev() {
+            writer.write_record([
+                name,
+                &session.scramble_type,
+                &stat.datetime.to_rfc3339_opts(SecondsFormat::AutoSi, false),
+                &write_time(stat.time),
+                write_penalty(stat.penalty),
+                stat.scramble(),
+                stat.comment(),
+            ])?;
+        }
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| Error::Msg(e.error().to_string()))?;
+    String::from_utf8(bytes).map_err(|e| Error::Msg(e.to_string()))
+}
+
+/// Reads stat from the record using given getter of the fields
+fn read_stat<'a>(get: impl Fn(Field) -> &'a str) -> Result<Stat, String> {
+    let date = get(Field::Date);
+    let datetime = read_date(date).ok_or(format!("invalid date '{date}'"))?;
+
+    let time = get(Field::Time);
+    let (time, penalty) = if time.eq_ignore_ascii_case("dnf") {
+        (Duration::ZERO, Penalty::Dnf)
+    } else {
+        let penalty = get(Field::Penalty);
+        (
+            read_time(time).ok_or(format!("invalid time '{time}'"))?,
+            read_penalty(penalty)
+                .ok_or(format!("invalid penalty '{penalty}'"))?,
+        )
+    };
+
+    let mut stat = Stat::new(
+        time,
+        penalty,
+        get(Field::Scramble).to_owned(),
+        get(Field::Comment).to_owned(),
+    );
+    stat.datetime = datetime;
+    Ok(stat)
+}
+
+/// Writes time in seconds, trailing zeros after milliseconds are omitted
+fn write_time(time: Duration) -> String {
+    let text = format!("{}.{:09}", time.as_secs(), time.subsec_nanos());
+    let len = text.trim_end_matches('0').len().max(text.len() - 6);
+    text[..len].to_owned()
+}
+
+/// Reads time in seconds (`12.345`) or in minutes and seconds (`1:02.345`)
+fn read_time(text: &str) -> Option<Duration> {
+    let (minutes, seconds) = match text.rsplit_once(':') {
+        Some((minutes, seconds)) => (minutes.parse::<u64>().ok()?, seconds),
+        None => (0, text),
+    };
+    let (secs, frac) = seconds.split_once('.').unwrap_or((seconds, ""));
+    if frac.len() > 9 || !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = format!("{frac:0<9}").parse().ok()?;
+    Some(Duration::new(
+        minutes * 60 + secs.parse::<u64>().ok()?,
+        nanos,
+    ))
+}
+
+/// Writes penalty
+fn write_penalty(penalty: Penalty) -> &'static str {
+    match penalty {
+        Penalty::None => "",
+        Penalty::PlusTwo => "+2",
+        Penalty::Dnf => "DNF",
+    }
+}
+
+/// Reads penalty, empty text is no penalty
+fn read_penalty(text: &str) -> Option<Penalty> {
+    match text.to_lowercase().as_str() {
+        "" | "0" | "none" | "ok" => Some(Penalty::None),
+        "+2" | "2" => Some(Penalty::PlusTwo),
+        "dnf" => Some(Penalty::Dnf),
+        _ => None,
+    }
+}
+
+/// Reads date in ISO 8601 format, date without offset is in local time
+fn read_date(text: &str) -> Option<DateTime<Local>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Local));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .and_then(|d| d.and_local_timezone(Local).earliest())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Local, TimeZone};
+
+    use super::{read, write, Columns};
+    use crate::stats::{
+        stat::{Penalty, Stat},
+        stats::Stats,
+    };
+
+    /// Creates solve done at given time in milliseconds since the epoch
+    fn stat(time: u64, penalty: Penalty, comment: &str, millis: i64) -> Stat {
+        let mut stat = Stat::new(
+            Duration::from_millis(time),
+            penalty,
+            "R U R' U'".to_owned(),
+            comment.to_owned(),
+        );
+        stat.datetime = Local.timestamp_millis_opt(millis).unwrap();
+        stat
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut stats = Stats::default();
+        stats.add_session("Main", "3x3x3").unwrap();
+        stats.add_session("Big, \"cube\"", "5x5x5").unwrap();
+        // Sessions store the newest solve first
+        stats.get_session_mut("Main").unwrap().stats = vec![
+            stat(15_020, Penalty::Dnf, "pop", 1714633600000),
+            stat(9_870, Penalty::PlusTwo, "same time", 1714633500000),
+            stat(12_345, Penalty::None, "lucky, \"skip\"", 1714633500000),
+            stat(11_001, Penalty::None, "two\nlines", 1714633445123),
+        ];
+        stats.get_session_mut("Big, \"cube\"").unwrap().stats =
+            vec![stat(95_432, Penalty::None, "", 1714640000000)];
+
+        let sessions = ["Main".to_owned(), "Big, \"cube\"".to_owned()];
+        let text = write(&stats, &sessions).unwrap();
+        let import = read(&text, &Columns::default()).unwrap();
+        assert!(import.warnings.is_empty());
+        assert_eq!(import.sessions.len(), sessions.len());
+
+        for (imported, name) in import.sessions.iter().zip(&sessions) {
+            let session = stats.get_session(name).unwrap();
+            assert_eq!(&imported.name, name);
+            assert_eq!(imported.scramble_type, session.scramble_type);
+
+            let solves = |stats: &[Stat]| -> Vec<_> {
+                stats
+                    .iter()
+                    .map(|s| {
+                        (
+                            s.time,
+                            s.penalty,
+                            s.datetime,
+                            s.scramble().to_owned(),
+                            s.comment().to_owned(),
+                        )
+                    })
+                    .collect()
+            };
+            let mut expected = solves(&session.stats);
+            expected.reverse();
+            assert_eq!(solves(&imported.stats), expected);
+        }
+    }
+
+    #[test]
+    fn trimmed_fields() {
+        let text = "date , time, penalty\n\
+                    2024-05-02 08:04:05 , 12.345 , +2\n";
+        let import = read(text, &Columns::default()).unwrap();
+        assert!(import.warnings.is_empty());
+        let stat = &import.sessions[0].stats[0];
+        assert_eq!(stat.time, Duration::from_millis(12_345));
+        assert_eq!(stat.penalty, Penalty::PlusTwo);
+    }
+}
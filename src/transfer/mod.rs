@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use crate::{
+    error::Error,
+    stats::{stat::Stat, stats::Stats},
+    transfer::csv::Columns,
+};
+
+pub mod cstimer;
+pub mod csv;
+pub mod twisty;
+
+/// Format of the imported or exported file
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    CsTimer,
+    Csv,
+    TwistyTimer,
+}
+
+impl Format {
+    /// Reads sessions from given file contents
+    pub fn read(
+        &self,
+        text: &str,
+        options: &Options,
+    ) -> Result<Import, Error> {
+        match self {
+            Format::CsTimer => cstimer::read(text),
+            Format::Csv => csv::read(text, &options.columns),
+            Format::TwistyTimer => twisty::read(text),
+        }
+    }
+
+    /// Writes given sessions to the file contents
+    ///
+    /// **Parameters:**
+    /// * `stats` - stats containing the sessions
+    /// * `sessions` - names of the sessions to be written
+    pub fn write(
+        &self,
+        stats: &Stats,
+        sessions: &[String],
+    ) -> Result<String, Error> {
+        match self {
+            Format::CsTimer => cstimer::write(stats, sessions),
+            Format::Csv => csv::write(stats, sessions),
+            Format::TwistyTimer => Err(Error::Msg(
+                "export to Twisty Timer isn't supported".to_string(),
+            )),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cstimer" => Ok(Format::CsTimer),
+            "csv" => Ok(Format::Csv),
+            "twisty" | "twistytimer" => Ok(Format::TwistyTimer),
+            _ => Err(Error::Msg(format!("unknown format '{s}'"))),
+        }
+    }
+}
+
+/// Options of reading the imported file
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Mapping of the fields to the columns of the CSV file
+    pub columns: Columns,
+}
+
+/// What to do when imported session has the same name as existing one
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Collision {
+    /// Imported solves are added to the existing session
+    #[default]
+    Merge,
+    /// Imported session gets new name
+    Rename,
+}
+
+/// Session read from the imported file
+#[derive(Debug)]
+pub struct Imported {
+    pub name: String,
+    pub scramble_type: String,
+    pub stats: Vec<Stat>,
+}
+
+/// Sessions read from the imported file
+#[derive(Debug, Default)]
+pub struct Import {
+    pub sessions: Vec<Imported>,
+    /// Problems found in the file, which didn't stop the import
+    pub warnings: Vec<String>,
+}
+
+/// Summary of the session import
+#[derive(Debug)]
+pub struct Summary {
+    /// Name of the session in the imported file
+    pub source: String,
+    /// Name of the session the solves were imported to
+    pub session: String,
+    /// Whether the session was created
+    pub created: bool,
+    /// Number of imported solves
+    pub added: usize,
+    /// Number of solves, which were already in the session
+    pub skipped: usize,
+}
+
+/// Imports given sessions to the stats
+///
+/// **Parameters:**
+/// * `stats` - stats to import the sessions to
+/// * `import` - imported sessions
+/// * `collision` - what to do when session with the same name exists
+/// * `target` - session to import all the sessions to, when set
+///
+/// **Returns:**
+/// * Summary of each imported session
+pub fn import(
+    stats: &mut Stats,
+    import: Import,
+    collision: Collision,
+    target: Option<&str>,
+) -> Result<Vec<Summary>, Error> {
+    let name = |stats: &Stats, name: &str| match collision {
+        Collision::Merge => name.to_owned(),
+        Collision::Rename => free_name(stats, name),
+    };
+    let target = target.map(|t| name(stats, t));
+
+    let mut summaries = Vec::new();
+    for session in import.sessions {
+        let name = match &target {
+            Some(target) => target.clone(),
+            None => name(stats, &session.name),
+        };
+        let created = !stats.exists(&name);
+        if created {
+            stats.add_session(&name, &session.scramble_type)?;
+        }
+
+        let total = session.stats.len();
+        let added = stats.import(&name, session.stats)?;
+        summaries.push(Summary {
+            source: session.name,
+            session: name,
+            created,
+            added,
+            skipped: total - added,
+        });
+    }
+    Ok(summaries)
+}
+
+/// Gets session name based on given name, which isn't used yet
+fn free_name(stats: &Stats, name: &str) -> String {
+    if !stats.exists(name) {
+        return name.to_owned();
+    }
+    (2..)
+        .map(|i| format!("{name} ({i})"))
+        .find(|n| !stats.exists(n))
+        .unwrap_or_default()
+}
@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Local};
+use csv::{ReaderBuilder, StringRecord, Trim};
+
+use crate::{
+    error::Error,
+    stats::stat::{Penalty, Stat},
+    transfer::{Import, Imported},
+};
+
+/// Twisty Timer puzzle types and corresponding scramble types
+const PUZZLES: &[(&str, &str)] = &[
+    ("222", "2x2x2"),
+    ("333", "3x3x3"),
+    ("444", "4x4x4"),
+    ("555", "5x5x5"),
+    ("666", "6x6x6"),
+    ("777", "7x7x7"),
+    ("pyra", "Pyraminx"),
+    ("skewb", "Skewb"),
+    ("mega", "Megaminx"),
+    ("clock", "Clock"),
+    ("sq1", "Square-1"),
+];
+
+/// Twisty Timer adds the +2 penalty to the stored time
+const PLUS_TWO: Duration = Duration::from_millis(2000);
+
+/// Reads solves from the Twisty Timer backup file. Each line contains
+/// `Puzzle;Category;Time(millis);Date(millis);Scramble;Penalty;Comment`,
+/// each puzzle and category is imported as separate session
+pub fn read(text: &str) -> Result<Import, Error> {
+    let first = text.lines().next().unwrap_or_default();
+    let delimiter = if first.contains(';') { b';' } else { b',' };
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+
+    let mut import = Import::default();
+    let mut sessions = BTreeMap::new();
+    let mut unknown = BTreeMap::<String, usize>::new();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                import.warnings.push(format!("line {line}: {e}, skipped"));
+                continue;
+            }
+        };
+        let puzzle = record.get(0).unwrap_or_default();
+        if line == 1 && puzzle.eq_ignore_ascii_case("puzzle") {
+            continue;
+        }
+
+        let Some(scramble_type) = scramble_type(puzzle) else {
+            *unknown.entry(puzzle.to_owned()).or_default() += 1;
+            continue;
+        };
+        let stat = match read_stat(&record) {
+            Ok(stat) => stat,
+            Err(e) => {
+                import.warnings.push(format!("line {line}: {e}, skipped"));
+                continue;
+            }
+        };
+
+        let category = record.get(1).unwrap_or_default();
+        let name = match category {
+            "" => scramble_type.to_owned(),
+            _ => format!("{scramble_type} {category}"),
+        };
+        let id = *sessions.entry(name.clone()).or_insert_with(|| {
+            import.sessions.push(Imported {
+                name,
+                scramble_type: scramble_type.to_owned(),
+                stats: Vec::new(),
+            });
+            import.sessions.len() - 1
+        });
+        import.sessions[id].stats.push(stat);
+    }
+
+    for (puzzle, count) in unknown {
+        import.warnings.push(format!(
+            "unknown puzzle type '{puzzle}', {count} solves skipped"
+        ));
+    }
+    Ok(import)
+}
+
+/// Reads stat from the record, time and date are in milliseconds, penalty
+/// is 0 for no penalty, 1 for +2 and 2 for DNF
+fn read_stat(record: &StringRecord) -> Result<Stat, String> {
+    let get = |i| record.get(i).unwrap_or_default();
+
+    let time = get(2);
+    let time = time
+        .parse()
+        .map(Duration::from_millis)
+        .map_err(|_| format!("invalid time '{time}'"))?;
+    let date = get(3);
+    let datetime = date
+        .parse()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .ok_or(format!("invalid date '{date}'"))?;
+    let (time, penalty) = match get(5) {
+        "" | "0" => (time, Penalty::None),
+        "1" => (time.saturating_sub(PLUS_TWO), Penalty::PlusTwo),
+        "2" => (time, Penalty::Dnf),
+        penalty => return Err(format!("invalid penalty '{penalty}'")),
+    };
+
+    let mut stat =
+        Stat::new(time, penalty, get(4).to_owned(), get(6).to_owned());
+    stat.datetime = datetime.with_timezone(&Local);
+    Ok(stat)
+}
+
+/// Gets scramble type corresponding to given Twisty Timer puzzle type
+fn scramble_type(puzzle: &str) -> Option<&'static str> {
+    PUZZLES
+        .iter()
+        .find(|(p, _)| p.eq_ignore_ascii_case(puzzle))
+        .map(|(_, scramble_type)| *scramble_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::read;
+    use crate::stats::stat::Penalty;
+
+    const BACKUP: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/transfer/twisty.txt"
+    ));
+
+    #[test]
+    fn import() {
+        let import = read(BACKUP).unwrap();
+        let names: Vec<_> =
+            import.sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["3x3x3 Normal", "3x3x3 OH", "2x2x2 Normal"]);
+        assert_eq!(
+            import.warnings,
+            [
+                "line 7: invalid time 'fast', skipped",
+                "unknown puzzle type 'mirror', 1 solves skipped",
+            ]
+        );
+
+        let solves: Vec<_> = import.sessions[0]
+            .stats
+            .iter()
+            .map(|s| {
+                (
+                    s.time,
+                    s.penalty,
+                    s.comment(),
+                    s.datetime.timestamp_millis(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            solves,
+            [
+                (
+                    Duration::from_millis(12_345),
+                    Penalty::None,
+                    "",
+                    1714633445123
+                ),
+                // Twisty Timer stores the time with the +2 added
+                (
+                    Duration::from_millis(9_870),
+                    Penalty::PlusTwo,
+                    "lucky, nice",
+                    1714633500000
+                ),
+                (
+                    Duration::from_millis(15_020),
+                    Penalty::Dnf,
+                    "pop",
+                    1714633600000
+                ),
+            ]
+        );
+        assert_eq!(import.sessions[0].stats[0].scramble(), "R U R' U'");
+        assert_eq!(import.sessions[2].scramble_type, "2x2x2");
+    }
+}
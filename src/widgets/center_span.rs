@@ -0,0 +1,72 @@
+use termint::{buffer::Buffer, geometry::Coords, widgets::Widget};
+
+/// Widget that prints text word wrapped with each line centered
+///
+/// Span widget with center alignment doesn't count with its position when
+/// wrapping, so it can be used only on the left edge of the screen
+pub struct CenterSpan {
+    text: String,
+}
+
+impl CenterSpan {
+    /// Creates new center span
+    pub fn new<T: AsRef<str>>(text: T) -> Self {
+        Self {
+            text: text.as_ref().to_string(),
+        }
+    }
+}
+
+impl Widget for CenterSpan {
+    fn render(&self, buffer: &mut Buffer) {
+        let width = buffer.width();
+        let lines = self.wrap(width);
+        for (i, line) in lines.iter().take(buffer.height()).enumerate() {
+            let text: String = line.chars().take(width).collect();
+            let x = (width - text.chars().count()) / 2;
+            let pos = Coords::new(buffer.x() + x, buffer.y() + i);
+            buffer.set_str(&text, &pos);
+        }
+    }
+
+    fn height(&self, size: &Coords) -> usize {
+        self.wrap(size.x).len()
+    }
+
+    fn width(&self, _size: &Coords) -> usize {
+        self.text
+            .lines()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl CenterSpan {
+    /// Wraps the text to the lines of the given width, words longer than
+    /// the width get their own line
+    fn wrap(&self, width: usize) -> Vec<String> {
+        let mut lines = vec![];
+        for text in self.text.lines() {
+            let mut line = String::new();
+            for word in text.split_whitespace() {
+                let len = line.chars().count() + word.chars().count();
+                if !line.is_empty() && len + 1 > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl From<CenterSpan> for Box<dyn Widget> {
+    fn from(value: CenterSpan) -> Self {
+        Box::new(value)
+    }
+}
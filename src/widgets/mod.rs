@@ -1 +1,2 @@
+pub mod center_span;
 pub mod raw_span;